@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE sessions
+ADD COLUMN last_seen_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+ADD COLUMN user_agent TEXT,
+ADD COLUMN ip TEXT;
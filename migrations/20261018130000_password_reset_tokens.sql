@@ -0,0 +1,7 @@
+-- Add migration script here
+ALTER TABLE email_tokens
+DROP CONSTRAINT email_tokens_purpose_check;
+
+ALTER TABLE email_tokens
+ADD CONSTRAINT email_tokens_purpose_check
+    CHECK (purpose IN ('verify_email', 'password_reset'));
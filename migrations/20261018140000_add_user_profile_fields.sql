@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE users
+ADD COLUMN bio TEXT,
+ADD COLUMN avatar_url TEXT,
+ADD COLUMN links TEXT[] NOT NULL DEFAULT '{}';
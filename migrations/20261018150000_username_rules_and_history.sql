@@ -0,0 +1,29 @@
+-- Add migration script here
+
+-- on a case-insensitive clash one account keeps the name, the rest get a unique suffix
+UPDATE users u
+SET username = u.username || '_' || left(u.id::text, 8)
+WHERE EXISTS (
+    SELECT 1 FROM users other
+    WHERE lower(other.username) = lower(u.username)
+        AND other.id < u.id
+);
+
+ALTER TABLE users
+DROP CONSTRAINT users_username_key;
+
+CREATE UNIQUE INDEX users_username_lower_key ON users (lower(username));
+
+CREATE TABLE username_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    username TEXT NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    CONSTRAINT fk_username_history_user
+        FOREIGN KEY (user_id)
+        REFERENCES users(id)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX idx_username_history_username ON username_history (lower(username), changed_at DESC);
@@ -0,0 +1,27 @@
+-- Add migration script here
+
+-- failed sign-in counters, keyed by "email:<address>" or "ip:<address>"
+CREATE TABLE login_throttle (
+    key TEXT PRIMARY KEY,
+    failed_count INT NOT NULL DEFAULT 0,
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    locked_until TIMESTAMPTZ
+);
+
+-- append-only, rows are never updated or deleted by the app
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID,
+    event TEXT NOT NULL,
+    ip TEXT,
+    user_agent TEXT,
+    metadata JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    CONSTRAINT fk_audit_log_user
+        FOREIGN KEY (user_id)
+        REFERENCES users(id)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX idx_audit_log_user ON audit_log(user_id, created_at DESC);
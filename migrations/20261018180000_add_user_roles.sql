@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE users
+ADD COLUMN role TEXT NOT NULL DEFAULT 'user'
+    CHECK (role IN ('user', 'moderator', 'admin')),
+ADD COLUMN suspended_until TIMESTAMPTZ,
+ADD COLUMN suspension_reason TEXT;
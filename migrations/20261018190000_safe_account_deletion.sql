@@ -0,0 +1,16 @@
+-- Add migration script here
+-- messages outlive their author and show up as "deleted user"
+ALTER TABLE user_messages ALTER COLUMN user_id DROP NOT NULL;
+
+-- lets ownership of a room pass to its longest standing member
+ALTER TABLE members ADD COLUMN joined_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+-- deleting a user cascades to their todos, which used to trip over tagged ones
+ALTER TABLE tag_todo DROP CONSTRAINT fk_todo;
+ALTER TABLE tag_todo
+    ADD CONSTRAINT fk_todo FOREIGN KEY (todo_id) REFERENCES todos(id) ON DELETE CASCADE;
+
+-- legacy chat table, unused but still blocks deleting anyone who wrote in it
+ALTER TABLE messages DROP CONSTRAINT fk_message_sender;
+ALTER TABLE messages
+    ADD CONSTRAINT fk_message_sender FOREIGN KEY (sender_id) REFERENCES users(id) ON DELETE CASCADE;
@@ -0,0 +1,5 @@
+-- Add migration script here
+-- set when the owner deletes the account, the row is purged once the grace period is over
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX idx_users_deleted_at ON users (deleted_at) WHERE deleted_at IS NOT NULL;
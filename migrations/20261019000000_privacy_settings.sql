@@ -0,0 +1,21 @@
+-- Add migration script here
+-- per-section visibility, a missing row means every section is 'followers'
+CREATE TABLE privacy_settings (
+    user_id UUID PRIMARY KEY,
+    profile TEXT NOT NULL DEFAULT 'followers' CHECK (profile IN ('public', 'followers', 'private')),
+    daily_progress TEXT NOT NULL DEFAULT 'followers' CHECK (daily_progress IN ('public', 'followers', 'private')),
+    stats TEXT NOT NULL DEFAULT 'followers' CHECK (stats IN ('public', 'followers', 'private')),
+    room_memberships TEXT NOT NULL DEFAULT 'followers' CHECK (room_memberships IN ('public', 'followers', 'private')),
+    followers TEXT NOT NULL DEFAULT 'followers' CHECK (followers IN ('public', 'followers', 'private')),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    CONSTRAINT fk_privacy_settings_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- public accounts keep everything public, private ones were already limited to their followers
+INSERT INTO privacy_settings (user_id, profile, daily_progress, stats, room_memberships, followers)
+SELECT id, 'public', 'public', 'public', 'public', 'public'
+FROM users
+WHERE is_public;
+
+ALTER TABLE users DROP COLUMN is_public;
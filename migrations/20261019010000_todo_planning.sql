@@ -0,0 +1,11 @@
+-- Add migration script here
+-- P1 is the most urgent, todos without a priority are P4
+ALTER TABLE todos
+    ADD COLUMN priority TEXT NOT NULL DEFAULT 'P4' CHECK (priority IN ('P1', 'P2', 'P3', 'P4')),
+    ADD COLUMN due_on DATE,
+    ADD COLUMN due_time TIME,
+    ADD COLUMN estimated_minutes INTEGER,
+    ADD CONSTRAINT todos_due_time_needs_date CHECK (due_time IS NULL OR due_on IS NOT NULL),
+    ADD CONSTRAINT todos_estimated_minutes_range CHECK (estimated_minutes BETWEEN 1 AND 1440);
+
+CREATE INDEX idx_todos_user_due_on ON todos (user_id, due_on) WHERE due_on IS NOT NULL;
@@ -0,0 +1,51 @@
+-- Add migration script here
+-- recurring todos, copied into daily_progress_todos on every day their schedule matches
+CREATE TABLE missions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    category_id UUID NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    priority TEXT NOT NULL DEFAULT 'P4' CHECK (priority IN ('P1', 'P2', 'P3', 'P4')),
+    estimated_minutes INTEGER CHECK (estimated_minutes BETWEEN 1 AND 1440),
+    -- by_day holds ISO weekdays (1 = monday) for weekly missions, every_days the step for interval ones
+    frequency TEXT NOT NULL CHECK (frequency IN ('daily', 'weekdays', 'weekly', 'interval')),
+    by_day SMALLINT[] NOT NULL DEFAULT '{}',
+    every_days INTEGER CHECK (every_days BETWEEN 1 AND 365),
+    starts_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    ends_on DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    CONSTRAINT missions_weekly_needs_days CHECK (frequency <> 'weekly' OR cardinality(by_day) > 0),
+    CONSTRAINT missions_interval_needs_step CHECK ((frequency = 'interval') = (every_days IS NOT NULL)),
+    CONSTRAINT missions_ends_after_start CHECK (ends_on IS NULL OR ends_on >= starts_on),
+    CONSTRAINT fk_missions_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    CONSTRAINT fk_missions_category FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_missions_user ON missions (user_id);
+
+-- one row per mission and day, kept when the todo is deleted so it isn't created again
+CREATE TABLE mission_occurrences (
+    mission_id UUID NOT NULL,
+    day DATE NOT NULL,
+    todo_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    PRIMARY KEY (mission_id, day),
+    CONSTRAINT fk_mission_occurrences_mission FOREIGN KEY (mission_id) REFERENCES missions(id) ON DELETE CASCADE,
+    CONSTRAINT fk_mission_occurrences_todo FOREIGN KEY (todo_id) REFERENCES todos(id) ON DELETE SET NULL
+);
+
+CREATE FUNCTION mission_occurs_on(m missions, day DATE) RETURNS BOOLEAN
+LANGUAGE sql STABLE AS $$
+    SELECT day >= m.starts_on
+        AND (m.ends_on IS NULL OR day <= m.ends_on)
+        AND CASE m.frequency
+            WHEN 'daily' THEN true
+            WHEN 'weekdays' THEN EXTRACT(ISODOW FROM day) < 6
+            WHEN 'weekly' THEN EXTRACT(ISODOW FROM day)::smallint = ANY(m.by_day)
+            WHEN 'interval' THEN (day - m.starts_on) % m.every_days = 0
+            ELSE false
+        END
+$$;
@@ -0,0 +1,13 @@
+-- Add migration script here
+
+-- on a case-insensitive clash one account keeps the address, the rest get a marked one and need support to sign in
+UPDATE users u
+SET email = u.email || '.duplicate-' || left(u.id::text, 8),
+    email_verified_at = NULL
+WHERE EXISTS (
+    SELECT 1 FROM users other
+    WHERE lower(other.email) = lower(u.email)
+        AND other.id < u.id
+);
+
+CREATE UNIQUE INDEX users_email_lower_key ON users (lower(email));
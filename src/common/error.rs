@@ -17,6 +17,12 @@ pub enum AppError {
     Db(#[from] sqlx::Error),
     #[error(transparent)]
     NotFound(#[from] NotFoundError),
+    #[error(transparent)]
+    Unauthorized(#[from] AuthError),
+    #[error(transparent)]
+    Forbidden(#[from] ForbiddenError),
+    #[error("{0}")]
+    TooManyRequests(String),
 }
 
 #[derive(Debug, Error)]
@@ -32,7 +38,49 @@ pub enum NotFoundError {
     #[error("Room not found")]
     RoomNotFound,
     #[error("Daily progress room not found")]
-    DailyProgressNotFound
+    DailyProgressNotFound,
+    #[error("Daily progress todo not found")]
+    ProgressTodoNotFound,
+    #[error("Mission not found")]
+    MissionNotFound,
+    #[error("Session not found")]
+    SessionNotFound,
+    #[error("Token not found")]
+    TokenNotFound,
+    #[error("Message not found")]
+    MessageNotFound,
+    #[error("Export not found or expired")]
+    ExportNotFound,
+    #[error("Follow request not found")]
+    FollowRequestNotFound,
+    #[error("Block not found")]
+    BlockNotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("Invalid email or password")]
+    InvalidCredentials,
+    #[error("Refresh token is missing or invalid")]
+    InvalidRefreshToken,
+    #[error("Refresh token was already used, session revoked")]
+    RefreshTokenReused,
+    #[error("Session expired or revoked")]
+    SessionRevoked,
+    #[error("Login challenge expired, sign in again")]
+    InvalidLoginChallenge,
+    #[error("Invalid two-factor code")]
+    InvalidSecondFactor,
+}
+
+#[derive(Debug, Error)]
+pub enum ForbiddenError {
+    #[error("Verify your email before doing this")]
+    EmailNotVerified,
+    #[error("Your account is suspended until {until}: {reason}")]
+    AccountSuspended { until: time::Date, reason: String },
+    #[error("You don't have permission to do this")]
+    InsufficientRole,
 }
 
 #[derive(Debug, Error)]
@@ -62,7 +110,67 @@ pub enum ValidationError {
     #[error("Failed to create token")]
     FailedToCreateToken,
     #[error("User profile is private!")]
-    UnauthorizedAccess
+    UnauthorizedAccess,
+    #[error("Token name must be between 1 and 64 cherecters")]
+    InvalidTokenName,
+    #[error("Unknown or missing token scope")]
+    InvalidScope,
+    #[error("Token expiry must be between 1 and 365 days")]
+    InvalidTokenExpiry,
+    #[error("Email is already verified")]
+    EmailAlreadyVerified,
+    #[error("Token is invalid or expired")]
+    InvalidOrExpiredToken,
+    #[error("Unknown restricted action")]
+    InvalidRestrictedAction,
+    #[error("Username must be 3 to 30 cherecters, start with a letter and only use letters, numbers or _")]
+    InvalidUsername,
+    #[error("This username is reserved")]
+    ReservedUsername,
+    #[error("Username already taken")]
+    UsernameTaken,
+    #[error("Bio must be at most 280 cherecters")]
+    BioTooLong,
+    #[error("At most 5 links, each a valid http(s) url")]
+    InvalidLinks,
+    #[error("Current password is required to change email or password")]
+    CurrentPasswordRequired,
+    #[error("Two-factor authentication is already enabled")]
+    TwoFactorAlreadyEnabled,
+    #[error("Start two-factor enrollment first")]
+    TwoFactorNotEnrolled,
+    #[error("Invalid two-factor code")]
+    InvalidTwoFactorCode,
+    #[error("Unknown audit event")]
+    InvalidAuditEvent,
+    #[error("You can't change your own role")]
+    CannotChangeOwnRole,
+    #[error("Suspension reason must be between 1 and 500 cherecters")]
+    InvalidSuspensionReason,
+    #[error("Suspension must be between 1 and 3650 days")]
+    InvalidSuspensionPeriod,
+    #[error("User is not suspended")]
+    NotSuspended,
+    #[error("Export is still being prepared")]
+    ExportNotReady,
+    #[error("You can't follow yourself")]
+    CannotFollowSelf,
+    #[error("You can't block yourself")]
+    CannotBlockSelf,
+    #[error("Unblock this user first")]
+    UserBlocked,
+    #[error("Nothing to update")]
+    NothingToUpdate,
+    #[error("Use either tags_slug or add_tags/remove_tags, and don't add and remove the same tag")]
+    ConflictingTagChanges,
+    #[error("A due time needs a due date")]
+    DueTimeWithoutDate,
+    #[error("Estimate must be between 1 and 1440 minutes")]
+    InvalidEstimate,
+    #[error("Day must be a YYYY-MM-DD date")]
+    InvalidDay,
+    #[error("Weekly missions need at least one day, intervals are 1 to 365 days and a mission can't end before it starts")]
+    InvalidSchedule,
 }
 
 impl IntoResponse for AppError {
@@ -71,6 +179,9 @@ impl IntoResponse for AppError {
             AppError::Validation(e) => (StatusCode::BAD_REQUEST, e.to_string()),
             AppError::Db(error) => map_sqlx_error(error),
             AppError::NotFound(e) => (StatusCode::NOT_FOUND, e.to_string()),
+            AppError::Unauthorized(e) => (StatusCode::UNAUTHORIZED, e.to_string()),
+            AppError::Forbidden(e) => (StatusCode::FORBIDDEN, e.to_string()),
+            AppError::TooManyRequests(message) => (StatusCode::TOO_MANY_REQUESTS, message),
             AppError::Failed(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
         };
         (
@@ -87,12 +198,14 @@ fn map_sqlx_error(error: sqlx::Error) -> (StatusCode, String) {
         sqlx::Error::Database(db_error) => match db_error.code().as_deref() {
             Some("23505") => {
                 let message = match db_error.constraint() {
-                    Some("users_email_key") => "User already exits",
+                    Some("users_email_key") | Some("users_email_lower_key") => "User already exits",
+                    Some("users_username_lower_key") => "Username already taken",
                     _ => "Resource already exits",
                 };
                 (StatusCode::CONFLICT, message.into())
             }
             Some("23503") => (StatusCode::BAD_REQUEST, "Invalid referance value".into()),
+            Some("23514") => (StatusCode::BAD_REQUEST, "Invalid value".into()),
             Some("23502") => (StatusCode::BAD_REQUEST, "Missing required field".into()),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, "Database error".into()),
         },
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    common::error::AppError,
+    middleware::client_info::ClientInfo,
+    modules::{audit::model::AuditEvent, rooms::service::RoomService},
+    state::AppState,
+};
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// removes accounts whose deletion grace period is over, runs once at startup and then hourly
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = purge(&state).await {
+                eprintln!("account purge failed: {e}");
+            }
+        }
+    });
+}
+
+pub async fn purge(state: &AppState) -> Result<(), AppError> {
+    // one failing account shouldn't hold back the rest
+    for user_id in state.user_service.expired_deletions().await? {
+        if let Err(e) = purge_user(state, user_id).await {
+            eprintln!("failed to purge account {user_id}: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn purge_user(state: &AppState, user_id: Uuid) -> Result<(), AppError> {
+    // written first, deleting the user nulls user_id so the id is kept in metadata too
+    state
+        .audit_service
+        .record(
+            Some(user_id),
+            AuditEvent::AccountDeleted,
+            &ClientInfo::default(),
+            json!({ "user_id": user_id }),
+        )
+        .await?;
+
+    // the archives hold the account's data, they go before the rows pointing at them
+    state.export_service.delete_for_user(&user_id).await?;
+
+    let closed_rooms = state.user_service.delete(user_id).await?;
+
+    for room_id in &closed_rooms {
+        RoomService::close_room(state, room_id).await;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use crate::state::AppState;
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// deletes export archives whose download link has expired
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = state.export_service.cleanup_expired().await {
+                eprintln!("export cleanup failed: {e}");
+            }
+        }
+    });
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+use time::OffsetDateTime;
+
+use crate::state::AppState;
+
+const MISSIONS_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// opens today's (UTC) progress for everyone with a mission due and fills the missions in.
+// populating is idempotent, so running hourly covers the night without waiting for midnight.
+// a day opened before the user's own midnight is simply handed back when they open it themselves
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(MISSIONS_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let today = OffsetDateTime::now_utc().date();
+            if let Err(e) = state.mission_service.populate(today).await {
+                eprintln!("mission populate failed: {e}");
+            }
+        }
+    });
+}
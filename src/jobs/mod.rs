@@ -0,0 +1,3 @@
+pub mod account_purge;
+pub mod export_cleanup;
+pub mod missions;
@@ -0,0 +1,7 @@
+pub mod common;
+pub mod jobs;
+pub mod middleware;
+pub mod modules;
+pub mod routes;
+pub mod state;
+pub mod utils;
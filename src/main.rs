@@ -1,62 +1,49 @@
-use std::{collections::HashMap, env, sync::Arc};
-use tokio::sync::{Mutex, broadcast};
-use tower_http::cors::{Any, CorsLayer};
-
-use crate::{
-    modules::{
-        progress::service::ProgressService, rooms::service::RoomService,
-        todo::service::TodoService, user::service::UserService,
-    },
-    routes::create_app,
-    state::AppState,
-    utils::{config::Config, db::init_db_pool},
-};
+use std::net::SocketAddr;
+use tower_http::cors::CorsLayer;
+
 use axum::{
     http::{HeaderValue, Method, header},
     response::Result,
 };
 use dotenvy::dotenv;
-use jsonwebtoken::{DecodingKey, EncodingKey};
+use protfolio_backend::{
+    jobs,
+    routes::create_app,
+    state::{AppSettings, AppState},
+    utils::{config::Config, db::init_db_pool},
+};
 use sqlx::PgPool;
 
-mod common;
-mod middleware;
-mod modules;
-mod routes;
-mod state;
-mod utils;
-
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
 
     let db_url = Config::DatabaseUrl.from_env()?;
-    let secret = Config::JsonWebTokenSecret.from_env()?;
+    let settings = AppSettings::from_env()?;
 
     let pool: PgPool = init_db_pool(&db_url).await?;
 
-    let state: AppState = AppState {
-        pool: pool.clone(),
-        jwt_decoding: DecodingKey::from_secret(secret.as_bytes()),
-        jwt_encoding: EncodingKey::from_secret(secret.as_bytes()),
-        todo_service: TodoService::new(pool.clone()),
-        user_service: UserService::new(pool.clone()),
-        progress_service: ProgressService::new(pool.clone()),
-        room_service: RoomService::new(pool),
-        rooms: Arc::new(Mutex::new(HashMap::new())),
-    };
+    let state: AppState = AppState::new(pool, settings);
 
     let cors = CorsLayer::new()
         .allow_origin("http://localhost:3001".parse::<HeaderValue>().unwrap())
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
         .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
         .allow_credentials(true);
 
+    jobs::account_purge::spawn(state.clone());
+    jobs::export_cleanup::spawn(state.clone());
+    jobs::missions::spawn(state.clone());
+
     let app = create_app(state).layer(cors);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
 
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
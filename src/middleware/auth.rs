@@ -1,27 +1,154 @@
 use axum::{
     extract::{Request, State},
-    http::StatusCode,
+    http::{HeaderMap, Method, StatusCode, header},
     middleware::Next,
-    response::{Response, Result},
+    response::{IntoResponse, Response, Result},
 };
 use axum_extra::extract::CookieJar;
-use crate::{modules::user::model::UserId, state::AppState, utils::jwt::verify_jwt_token};
+use uuid::Uuid;
+
+use crate::{
+    common::error::AppError,
+    modules::{
+        session::model::SessionId,
+        token::model::{AccessScopes, Resource, TOKEN_PREFIX, parse_scopes},
+        user::model::{Role, Suspension, UserId},
+    },
+    state::AppState,
+    utils::jwt::verify_jwt_token,
+};
+
+pub struct Authenticated {
+    pub user_id: Uuid,
+    // only set for browser sessions, personal access tokens have none
+    pub session_id: Option<Uuid>,
+    pub scopes: AccessScopes,
+    pub role: Role,
+    pub suspension: Option<Suspension>,
+}
 
 pub async fn auth_middleware(
     State(state): State<AppState>,
     jar: CookieJar,
     mut req: Request,
     next: Next,
+) -> Result<Response, Response> {
+    let token = extract_token(&jar, req.headers())
+        .ok_or_else(|| StatusCode::UNAUTHORIZED.into_response())?;
+
+    let auth = authenticate(&state, &token)
+        .await
+        .map_err(IntoResponse::into_response)?;
+
+    // the token stays valid, it just can't be used until the suspension ends
+    if let Some(suspension) = auth.suspension {
+        return Err(AppError::Forbidden(suspension.into()).into_response());
+    }
+
+    req.extensions_mut().insert(UserId(auth.user_id));
+    if let Some(session_id) = auth.session_id {
+        req.extensions_mut().insert(SessionId(session_id));
+    }
+    req.extensions_mut().insert(auth.scopes);
+    req.extensions_mut().insert(auth.role);
+
+    Ok(next.run(req).await)
+}
+
+// public routes still want to know who is asking; a missing or stale token just means anonymous
+pub async fn optional_auth_middleware(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    if let Some(token) = extract_token(&jar, req.headers())
+        && let Ok(auth) = authenticate(&state, &token).await
+        && auth.suspension.is_none()
+    {
+        req.extensions_mut().insert(UserId(auth.user_id));
+        if let Some(session_id) = auth.session_id {
+            req.extensions_mut().insert(SessionId(session_id));
+        }
+        req.extensions_mut().insert(auth.scopes);
+        req.extensions_mut().insert(auth.role);
+    }
+
+    next.run(req).await
+}
+
+// an explicit `Authorization: Bearer` header wins over the browser cookie
+pub fn extract_token(jar: &CookieJar, headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string())
+        .or_else(|| jar.get("jwt").map(|c| c.value().to_string()))
+}
+
+pub async fn authenticate(state: &AppState, token: &str) -> Result<Authenticated, StatusCode> {
+    if token.starts_with(TOKEN_PREFIX) {
+        let owner = state
+            .token_service
+            .authenticate(token)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        return Ok(Authenticated {
+            user_id: owner.user_id,
+            session_id: None,
+            scopes: AccessScopes::Limited(parse_scopes(&owner.scopes)),
+            role: owner.role,
+            suspension: Suspension::active(owner.suspended_until, owner.suspension_reason),
+        });
+    }
+
+    let token_data = verify_jwt_token(token, state.jwt_decoding.clone()).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    // role and suspension come from the database so they apply before the jwt expires
+    let owner = state
+        .session_service
+        .touch(&token_data.session_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    Ok(Authenticated {
+        user_id: token_data.user_id,
+        session_id: Some(token_data.session_id),
+        scopes: AccessScopes::All,
+        role: owner.role,
+        suspension: Suspension::active(owner.suspended_until, owner.suspension_reason),
+    })
+}
+
+pub async fn require_scope(
+    State(resource): State<Resource>,
+    req: Request,
+    next: Next,
 ) -> Result<Response, StatusCode> {
-    let token = jar
-        .get("jwt")
-        .map(|c| c.value().to_string())
+    let scopes = req
+        .extensions()
+        .get::<AccessScopes>()
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
+    // a websocket upgrade is a GET but lets the client post messages
+    let write = !matches!(*req.method(), Method::GET | Method::HEAD)
+        || req.headers().contains_key(header::UPGRADE);
+
+    if !scopes.allows(resource.scope(write)) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(next.run(req).await)
+}
 
-    let token_data = verify_jwt_token(&token, state.jwt_decoding).map_err(|_| StatusCode::UNAUTHORIZED)?;
-   
-    req.extensions_mut().insert(UserId(token_data.user_id));
+pub async fn require_session(req: Request, next: Next) -> Result<Response, StatusCode> {
+    if req.extensions().get::<SessionId>().is_none() {
+        return Err(StatusCode::FORBIDDEN);
+    }
 
     Ok(next.run(req).await)
 }
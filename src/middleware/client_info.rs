@@ -0,0 +1,107 @@
+use std::{convert::Infallible, net::{IpAddr, SocketAddr}, sync::Arc};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts},
+};
+
+use crate::{common::error::AppError, utils::config::Config};
+
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+// proxies allowed to tell us the client address through X-Forwarded-For,
+// installed as a request extension by create_app
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Arc<Vec<IpAddr>>);
+
+impl TrustedProxies {
+    pub fn new(proxies: Vec<IpAddr>) -> Self {
+        Self(Arc::new(proxies))
+    }
+
+    // comma separated addresses in TRUSTED_PROXIES, nobody is trusted when unset
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let proxies = Config::TrustedProxies.parse_or(String::new())?;
+
+        let proxies = proxies
+            .split(',')
+            .map(str::trim)
+            .filter(|proxy| !proxy.is_empty())
+            .map(|proxy| {
+                proxy
+                    .parse()
+                    .map_err(|_| AppError::Failed(format!("Invalid address in TRUSTED_PROXIES: {proxy}")))
+            })
+            .collect::<Result<Vec<IpAddr>, AppError>>()?;
+
+        Ok(Self::new(proxies))
+    }
+
+    fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.contains(ip)
+    }
+
+    // the forwarded header is only believed when a trusted proxy sent it, and then only up to the
+    // right-most hop we don't trust, everything left of that could have been written by the client
+    pub fn client_ip(&self, peer: Option<IpAddr>, forwarded_for: Option<&str>) -> Option<IpAddr> {
+        let peer = peer?;
+
+        if !self.contains(&peer) {
+            return Some(peer);
+        }
+
+        let Some(forwarded_for) = forwarded_for else {
+            return Some(peer);
+        };
+
+        let mut client = peer;
+        for hop in forwarded_for.rsplit(',') {
+            let Ok(hop) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+
+            client = hop;
+            if !self.contains(&hop) {
+                break;
+            }
+        }
+
+        Some(client)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        let forwarded_for = parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok());
+
+        let ip = parts
+            .extensions
+            .get::<TrustedProxies>()
+            .cloned()
+            .unwrap_or_default()
+            .client_ip(peer, forwarded_for)
+            .map(|ip| ip.to_string());
+
+        Ok(Self { ip, user_agent })
+    }
+}
@@ -0,0 +1,62 @@
+use std::marker::PhantomData;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, ForbiddenError},
+    modules::user::model::{Role, UserId},
+};
+
+pub trait RoleRequirement {
+    const MINIMUM: Role;
+}
+
+pub struct ModeratorRole;
+
+impl RoleRequirement for ModeratorRole {
+    const MINIMUM: Role = Role::Moderator;
+}
+
+pub struct AdminRole;
+
+impl RoleRequirement for AdminRole {
+    const MINIMUM: Role = Role::Admin;
+}
+
+// handler argument that rejects callers below `R::MINIMUM`, needs auth_middleware in front of it
+pub struct RequireRole<R: RoleRequirement> {
+    pub user_id: Uuid,
+    pub role: Role,
+    _requirement: PhantomData<R>,
+}
+
+pub type Moderator = RequireRole<ModeratorRole>;
+pub type Admin = RequireRole<AdminRole>;
+
+impl<S: Send + Sync, R: RoleRequirement> FromRequestParts<S> for RequireRole<R> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_id = parts
+            .extensions
+            .get::<UserId>()
+            .ok_or(AppError::Failed("Role guard used without auth middleware".into()))?
+            .0;
+
+        let role = *parts
+            .extensions
+            .get::<Role>()
+            .ok_or(AppError::Failed("Role guard used without auth middleware".into()))?;
+
+        if role < R::MINIMUM {
+            return Err(AppError::Forbidden(ForbiddenError::InsufficientRole));
+        }
+
+        Ok(Self {
+            user_id,
+            role,
+            _requirement: PhantomData,
+        })
+    }
+}
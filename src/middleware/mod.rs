@@ -1 +1,4 @@
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod client_info;
+pub mod guard;
+pub mod rate_limit;
\ No newline at end of file
@@ -0,0 +1,99 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{Response, Result},
+};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::{middleware::client_info::ClientInfo, utils::token::hash_token};
+
+// the rate limited routes only take small json bodies
+const MAX_BODY_BYTES: usize = 16 * 1024;
+
+// fixed window counters kept in memory so they reset on restart. a request counts against its
+// client ip and against the email or token it targets, spreading requests over many addresses
+// or over many targets doesn't get around the limit
+#[derive(Clone)]
+pub struct RateLimiter {
+    max_requests: u32,
+    window: Duration,
+    // the json field naming what the request targets, e.g. "email"
+    target_field: &'static str,
+    hits: Arc<Mutex<HashMap<String, (Instant, u32)>>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_requests: u32, window: Duration, target_field: &'static str) -> Self {
+        Self {
+            max_requests,
+            window,
+            target_field,
+            hits: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    // every key is counted, the request is allowed only if none of them is over the limit
+    async fn allow(&self, keys: &[String]) -> bool {
+        let now = Instant::now();
+        let mut hits = self.hits.lock().await;
+
+        // drop expired windows so the map doesn't grow without bound
+        hits.retain(|_, (started, _)| now.duration_since(*started) < self.window);
+
+        let mut allowed = true;
+        for key in keys {
+            let (_, count) = hits.entry(key.clone()).or_insert((now, 0));
+            *count += 1;
+
+            if *count > self.max_requests {
+                allowed = false;
+            }
+        }
+
+        allowed
+    }
+
+    // hashed so reset tokens aren't kept around in memory
+    fn target(&self, body: &[u8]) -> Option<String> {
+        let body: Value = serde_json::from_slice(body).ok()?;
+        let target = body.get(self.target_field)?.as_str()?.trim().to_lowercase();
+
+        (!target.is_empty()).then(|| hash_token(&target))
+    }
+}
+
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    client: ClientInfo,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let (parts, body) = req.into_parts();
+    let body = to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE)?;
+
+    // without a known address only the target is counted, clients never share a bucket
+    let mut keys = Vec::new();
+    if let Some(ip) = client.ip {
+        keys.push(format!("ip:{ip}"));
+    }
+    if let Some(target) = limiter.target(&body) {
+        keys.push(format!("target:{target}"));
+    }
+
+    if !limiter.allow(&keys).await {
+        return Err(StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    Ok(next.run(Request::from_parts(parts, Body::from(body))).await)
+}
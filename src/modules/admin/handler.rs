@@ -0,0 +1,192 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    common::{
+        error::{AppError, ValidationError},
+        response::ApiResponse,
+    },
+    middleware::{
+        client_info::ClientInfo,
+        guard::{Admin, Moderator},
+    },
+    modules::{
+        admin::model::{AdminAuditQueryDto, SuspendUserDto, UpdateRoleDto, UserSearchDto},
+        audit::model::{AuditEvent, AuditFilter, AuditQueryDto},
+        rooms::{model::ServerEvent, service::RoomService},
+        user::model::Suspension,
+    },
+    state::AppState,
+};
+
+pub async fn search_users_handler(
+    State(state): State<AppState>,
+    _moderator: Moderator,
+    Query(query): Query<UserSearchDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let users = state.admin_service.search_users(query.into()).await?;
+
+    Ok(Json(ApiResponse::success("fetch users successfuly", users)))
+}
+
+pub async fn update_role_handler(
+    State(state): State<AppState>,
+    admin: Admin,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateRoleDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    // keeps at least one admin around
+    if admin.user_id == user_id {
+        return Err(AppError::Validation(ValidationError::CannotChangeOwnRole));
+    }
+
+    state.admin_service.set_role(&user_id, payload.role).await?;
+
+    state
+        .audit_service
+        .record(
+            Some(user_id),
+            AuditEvent::RoleChanged,
+            &client,
+            json!({ "role": payload.role, "by": admin.user_id }),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success("role updated successfuly", None::<()>)))
+}
+
+pub async fn suspend_user_handler(
+    State(state): State<AppState>,
+    moderator: Moderator,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SuspendUserDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let suspension: Suspension = payload.try_into()?;
+
+    state
+        .admin_service
+        .suspend(moderator.role, &user_id, &suspension)
+        .await?;
+
+    RoomService::disconnect_user(
+        &state,
+        &user_id,
+        ServerEvent::AccountSuspended {
+            reason: suspension.reason.clone(),
+            until: suspension.until.unix_timestamp(),
+        },
+    )
+    .await;
+
+    state
+        .audit_service
+        .record(
+            Some(user_id),
+            AuditEvent::AccountSuspended,
+            &client,
+            json!({
+                "reason": suspension.reason,
+                "until": suspension.until.unix_timestamp(),
+                "by": moderator.user_id,
+            }),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success("user suspended successfuly", None::<()>)))
+}
+
+pub async fn lift_suspension_handler(
+    State(state): State<AppState>,
+    moderator: Moderator,
+    client: ClientInfo,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    state
+        .admin_service
+        .lift_suspension(moderator.role, &user_id)
+        .await?;
+
+    state
+        .audit_service
+        .record(
+            Some(user_id),
+            AuditEvent::SuspensionLifted,
+            &client,
+            json!({ "by": moderator.user_id }),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success("suspension lifted successfuly", None::<()>)))
+}
+
+pub async fn delete_room_handler(
+    State(state): State<AppState>,
+    moderator: Moderator,
+    client: ClientInfo,
+    Path(room_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    state.admin_service.delete_room(&room_id).await?;
+
+    RoomService::close_room(&state, &room_id).await;
+
+    state
+        .audit_service
+        .record(
+            Some(moderator.user_id),
+            AuditEvent::RoomDeleted,
+            &client,
+            json!({ "room_id": room_id }),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success("room deleted successfuly", None::<()>)))
+}
+
+pub async fn delete_message_handler(
+    State(state): State<AppState>,
+    moderator: Moderator,
+    client: ClientInfo,
+    Path(message_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let room_id = state.admin_service.delete_message(&message_id).await?;
+
+    RoomService::broadcast_message(&state, &room_id, ServerEvent::MessageDeleted { id: message_id })
+        .await;
+
+    state
+        .audit_service
+        .record(
+            Some(moderator.user_id),
+            AuditEvent::MessageDeleted,
+            &client,
+            json!({ "message_id": message_id, "room_id": room_id }),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success("message deleted successfuly", None::<()>)))
+}
+
+pub async fn audit_log_handler(
+    State(state): State<AppState>,
+    _admin: Admin,
+    Query(query): Query<AdminAuditQueryDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let filter = AuditFilter::from_query(
+        query.user_id,
+        AuditQueryDto {
+            event: query.event,
+            limit: query.limit,
+            offset: query.offset,
+        },
+    )?;
+
+    let entries = state.audit_service.search(filter).await?;
+
+    Ok(Json(ApiResponse::success("fetch audit log successfuly", entries)))
+}
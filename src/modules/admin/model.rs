@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    common::error::ValidationError,
+    modules::user::model::{Role, Suspension},
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+const MAX_SUSPENSION_DAYS: i64 = 3650;
+const MAX_REASON_LENGTH: usize = 500;
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct AdminUserView {
+    pub id: Uuid,
+    pub name: String,
+    pub username: String,
+    pub email: String,
+    pub role: Role,
+    pub email_verified: bool,
+    pub suspended_until: Option<OffsetDateTime>,
+    pub suspension_reason: Option<String>,
+    pub deleted_at: Option<OffsetDateTime>,
+}
+
+// `q` matches name, username or email
+#[derive(Debug, Deserialize)]
+pub struct UserSearchDto {
+    pub q: Option<String>,
+    pub role: Option<Role>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub struct UserSearch {
+    pub pattern: Option<String>,
+    pub role: Option<Role>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl From<UserSearchDto> for UserSearch {
+    fn from(value: UserSearchDto) -> Self {
+        let pattern = value
+            .q
+            .map(|q| q.trim().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
+            .filter(|q| !q.is_empty())
+            .map(|q| format!("%{q}%"));
+
+        Self {
+            pattern,
+            role: value.role,
+            limit: value.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+            offset: value.offset.unwrap_or(0).max(0),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRoleDto {
+    pub role: Role,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuspendUserDto {
+    pub reason: String,
+    pub days: i64,
+}
+
+impl TryFrom<SuspendUserDto> for Suspension {
+    type Error = ValidationError;
+    fn try_from(value: SuspendUserDto) -> Result<Self, Self::Error> {
+        let reason = value.reason.trim();
+
+        if reason.is_empty() || reason.chars().count() > MAX_REASON_LENGTH {
+            return Err(ValidationError::InvalidSuspensionReason);
+        }
+
+        if !(1..=MAX_SUSPENSION_DAYS).contains(&value.days) {
+            return Err(ValidationError::InvalidSuspensionPeriod);
+        }
+
+        Ok(Self {
+            reason: reason.to_string(),
+            until: OffsetDateTime::now_utc() + Duration::days(value.days),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminAuditQueryDto {
+    pub user_id: Option<Uuid>,
+    pub event: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
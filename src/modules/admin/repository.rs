@@ -0,0 +1,151 @@
+use sqlx::{PgPool, Result};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, NotFoundError},
+    modules::{
+        admin::model::{AdminUserView, UserSearch},
+        user::model::Role,
+    },
+};
+
+pub struct AdminRepo;
+
+impl AdminRepo {
+    pub async fn search_users(pool: &PgPool, search: &UserSearch) -> Result<Vec<AdminUserView>> {
+        let users = sqlx::query_as!(
+            AdminUserView,
+            r#"
+            SELECT id, name, username, email, role AS "role: Role",
+                email_verified_at IS NOT NULL AS "email_verified!",
+                suspended_until, suspension_reason, deleted_at
+            FROM users
+            WHERE ($1::text IS NULL OR name ILIKE $1 OR username ILIKE $1 OR email ILIKE $1)
+                AND ($2::text IS NULL OR role = $2)
+            ORDER BY lower(username)
+            LIMIT $3 OFFSET $4
+            "#,
+            search.pattern,
+            search.role as Option<Role>,
+            search.limit,
+            search.offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    pub async fn fetch_role(pool: &PgPool, user_id: &Uuid) -> Result<Option<Role>> {
+        let role = sqlx::query_scalar!(
+            r#"
+            SELECT role AS "role: Role"
+            FROM users
+            WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(role)
+    }
+
+    pub async fn set_role(pool: &PgPool, user_id: &Uuid, role: Role) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET role = $1
+            WHERE id = $2
+            "#,
+            role as Role,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(NotFoundError::UserNotFound));
+        }
+
+        Ok(())
+    }
+
+    pub async fn suspend(
+        pool: &PgPool,
+        user_id: &Uuid,
+        until: OffsetDateTime,
+        reason: &str,
+    ) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET suspended_until = $1, suspension_reason = $2
+            WHERE id = $3
+            "#,
+            until,
+            reason,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(NotFoundError::UserNotFound));
+        }
+
+        Ok(())
+    }
+
+    // false when the user wasn't suspended to begin with
+    pub async fn lift_suspension(pool: &PgPool, user_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET suspended_until = NULL, suspension_reason = NULL
+            WHERE id = $1 AND suspended_until > now()
+            "#,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete_room(pool: &PgPool, room_id: &Uuid) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM rooms
+            WHERE id = $1
+            "#,
+            room_id
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(NotFoundError::RoomNotFound));
+        }
+
+        Ok(())
+    }
+
+    // returns the room the message was posted in
+    pub async fn delete_message(pool: &PgPool, message_id: &Uuid) -> Result<Uuid, AppError> {
+        let room_id = sqlx::query_scalar!(
+            r#"
+            DELETE FROM user_messages
+            WHERE id = $1
+            RETURNING room_id
+            "#,
+            message_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::NotFound(NotFoundError::MessageNotFound))?;
+
+        Ok(room_id)
+    }
+}
@@ -0,0 +1,76 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, ForbiddenError, NotFoundError, ValidationError},
+    modules::{
+        admin::{
+            model::{AdminUserView, UserSearch},
+            repository::AdminRepo,
+        },
+        user::model::{Role, Suspension},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct AdminService {
+    pool: PgPool,
+}
+
+impl AdminService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn search_users(&self, search: UserSearch) -> Result<Vec<AdminUserView>, AppError> {
+        let users = AdminRepo::search_users(&self.pool, &search).await?;
+
+        Ok(users)
+    }
+
+    pub async fn set_role(&self, user_id: &Uuid, role: Role) -> Result<(), AppError> {
+        AdminRepo::set_role(&self.pool, user_id, role).await
+    }
+
+    // staff can only suspend accounts ranked below them, which also rules out suspending yourself
+    pub async fn suspend(
+        &self,
+        actor_role: Role,
+        user_id: &Uuid,
+        suspension: &Suspension,
+    ) -> Result<(), AppError> {
+        self.ensure_outranks(actor_role, user_id).await?;
+
+        AdminRepo::suspend(&self.pool, user_id, suspension.until, &suspension.reason).await
+    }
+
+    pub async fn lift_suspension(&self, actor_role: Role, user_id: &Uuid) -> Result<(), AppError> {
+        self.ensure_outranks(actor_role, user_id).await?;
+
+        if !AdminRepo::lift_suspension(&self.pool, user_id).await? {
+            return Err(AppError::Validation(ValidationError::NotSuspended));
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_outranks(&self, actor_role: Role, user_id: &Uuid) -> Result<(), AppError> {
+        let target_role = AdminRepo::fetch_role(&self.pool, user_id)
+            .await?
+            .ok_or(AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        if target_role >= actor_role {
+            return Err(AppError::Forbidden(ForbiddenError::InsufficientRole));
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_room(&self, room_id: &Uuid) -> Result<(), AppError> {
+        AdminRepo::delete_room(&self.pool, room_id).await
+    }
+
+    pub async fn delete_message(&self, message_id: &Uuid) -> Result<Uuid, AppError> {
+        AdminRepo::delete_message(&self.pool, message_id).await
+    }
+}
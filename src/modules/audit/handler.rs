@@ -0,0 +1,25 @@
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+};
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    modules::{
+        audit::model::{AuditFilter, AuditQueryDto},
+        user::model::UserId,
+    },
+    state::AppState,
+};
+
+pub async fn my_audit_log_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Query(query): Query<AuditQueryDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let filter = AuditFilter::from_query(Some(user_id.0), query)?;
+
+    let entries = state.audit_service.search(filter).await?;
+
+    Ok(Json(ApiResponse::success("fetch audit log successfuly", entries)))
+}
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::common::error::ValidationError;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEvent {
+    SignUp,
+    LoginSucceeded,
+    LoginFailed,
+    Logout,
+    VisibilityChanged,
+    PasswordChanged,
+    PasswordReset,
+    EmailChanged,
+    TwoFactorEnabled,
+    TwoFactorDisabled,
+    AccountDeletionScheduled,
+    AccountRestored,
+    AccountDeleted,
+    AccountLocked,
+    IpLocked,
+    RoleChanged,
+    AccountSuspended,
+    SuspensionLifted,
+    RoomDeleted,
+    MessageDeleted,
+}
+
+impl AuditEvent {
+    const ALL: [AuditEvent; 20] = [
+        AuditEvent::SignUp,
+        AuditEvent::LoginSucceeded,
+        AuditEvent::LoginFailed,
+        AuditEvent::Logout,
+        AuditEvent::VisibilityChanged,
+        AuditEvent::PasswordChanged,
+        AuditEvent::PasswordReset,
+        AuditEvent::EmailChanged,
+        AuditEvent::TwoFactorEnabled,
+        AuditEvent::TwoFactorDisabled,
+        AuditEvent::AccountDeletionScheduled,
+        AuditEvent::AccountRestored,
+        AuditEvent::AccountDeleted,
+        AuditEvent::AccountLocked,
+        AuditEvent::IpLocked,
+        AuditEvent::RoleChanged,
+        AuditEvent::AccountSuspended,
+        AuditEvent::SuspensionLifted,
+        AuditEvent::RoomDeleted,
+        AuditEvent::MessageDeleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEvent::SignUp => "sign_up",
+            AuditEvent::LoginSucceeded => "login_succeeded",
+            AuditEvent::LoginFailed => "login_failed",
+            AuditEvent::Logout => "logout",
+            AuditEvent::VisibilityChanged => "visibility_changed",
+            AuditEvent::PasswordChanged => "password_changed",
+            AuditEvent::PasswordReset => "password_reset",
+            AuditEvent::EmailChanged => "email_changed",
+            AuditEvent::TwoFactorEnabled => "two_factor_enabled",
+            AuditEvent::TwoFactorDisabled => "two_factor_disabled",
+            AuditEvent::AccountDeletionScheduled => "account_deletion_scheduled",
+            AuditEvent::AccountRestored => "account_restored",
+            AuditEvent::AccountDeleted => "account_deleted",
+            AuditEvent::AccountLocked => "account_locked",
+            AuditEvent::IpLocked => "ip_locked",
+            AuditEvent::RoleChanged => "role_changed",
+            AuditEvent::AccountSuspended => "account_suspended",
+            AuditEvent::SuspensionLifted => "suspension_lifted",
+            AuditEvent::RoomDeleted => "room_deleted",
+            AuditEvent::MessageDeleted => "message_deleted",
+        }
+    }
+}
+
+impl FromStr for AuditEvent {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditEvent::ALL
+            .into_iter()
+            .find(|event| event.as_str() == s)
+            .ok_or(ValidationError::InvalidAuditEvent)
+    }
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub event: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub metadata: serde_json::Value,
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditQueryDto {
+    pub event: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub struct AuditFilter {
+    pub user_id: Option<Uuid>,
+    pub event: Option<AuditEvent>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl AuditFilter {
+    pub fn from_query(user_id: Option<Uuid>, query: AuditQueryDto) -> Result<Self, ValidationError> {
+        let event = query.event.as_deref().map(AuditEvent::from_str).transpose()?;
+
+        Ok(Self {
+            user_id,
+            event,
+            limit: query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+            offset: query.offset.unwrap_or(0).max(0),
+        })
+    }
+}
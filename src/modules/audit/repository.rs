@@ -0,0 +1,57 @@
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+use crate::{
+    middleware::client_info::ClientInfo,
+    modules::audit::model::{AuditEntry, AuditEvent, AuditFilter},
+};
+
+pub struct AuditRepo;
+
+impl AuditRepo {
+    pub async fn insert(
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        event: AuditEvent,
+        client: &ClientInfo,
+        metadata: serde_json::Value,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_log (user_id, event, ip, user_agent, metadata)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            user_id,
+            event.as_str(),
+            client.ip,
+            client.user_agent,
+            metadata
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn search(pool: &PgPool, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+        let entries = sqlx::query_as!(
+            AuditEntry,
+            r#"
+            SELECT id, user_id, event, ip, user_agent, metadata, created_at
+            FROM audit_log
+            WHERE ($1::uuid IS NULL OR user_id = $1)
+                AND ($2::text IS NULL OR event = $2)
+            ORDER BY created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            filter.user_id,
+            filter.event.map(|event| event.as_str()),
+            filter.limit,
+            filter.offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
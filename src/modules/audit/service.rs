@@ -0,0 +1,40 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    common::error::AppError,
+    middleware::client_info::ClientInfo,
+    modules::audit::{
+        model::{AuditEntry, AuditEvent, AuditFilter},
+        repository::AuditRepo,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct AuditService {
+    pool: PgPool,
+}
+
+impl AuditService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        user_id: Option<Uuid>,
+        event: AuditEvent,
+        client: &ClientInfo,
+        metadata: serde_json::Value,
+    ) -> Result<(), AppError> {
+        AuditRepo::insert(&self.pool, user_id, event, client, metadata).await?;
+
+        Ok(())
+    }
+
+    pub async fn search(&self, filter: AuditFilter) -> Result<Vec<AuditEntry>, AppError> {
+        let entries = AuditRepo::search(&self.pool, &filter).await?;
+
+        Ok(entries)
+    }
+}
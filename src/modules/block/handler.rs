@@ -0,0 +1,63 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    modules::{
+        block::model::BlockResponse,
+        follow::model::PageDto,
+        rooms::service::RoomService,
+        user::model::UserId,
+    },
+    state::AppState,
+};
+
+pub async fn block_user_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(username): Path<String>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let blocked = state.user_service.get_user_by_username(&username).await?;
+
+    state.block_service.block(&user_id.0, &blocked.id).await?;
+    RoomService::set_blocked(&state, &user_id.0, &blocked.id, true).await;
+
+    Ok(Json(ApiResponse::success(
+        "block successfuly",
+        BlockResponse {
+            username: blocked.username,
+            blocked: true,
+        },
+    )))
+}
+
+pub async fn unblock_user_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(username): Path<String>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let blocked = state.user_service.get_user_by_username(&username).await?;
+
+    state.block_service.unblock(&user_id.0, &blocked.id).await?;
+    RoomService::set_blocked(&state, &user_id.0, &blocked.id, false).await;
+
+    Ok(Json(ApiResponse::success(
+        "unblock successfuly",
+        BlockResponse {
+            username: blocked.username,
+            blocked: false,
+        },
+    )))
+}
+
+pub async fn blocked_users_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Query(page): Query<PageDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let blocked = state.block_service.blocked(&user_id.0, page.into()).await?;
+
+    Ok(Json(ApiResponse::success("fetch blocked users successfuly", blocked)))
+}
@@ -0,0 +1,20 @@
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct BlockResponse {
+    pub username: String,
+    pub blocked: bool,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct BlockedUser {
+    pub id: Uuid,
+    pub name: String,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub since: OffsetDateTime,
+}
@@ -0,0 +1,110 @@
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+use crate::modules::{block::model::BlockedUser, follow::model::Page};
+
+pub struct BlockRepo;
+
+impl BlockRepo {
+    // a block also ends any follow between the two users, in both directions
+    pub async fn block(pool: &PgPool, blocker_id: &Uuid, blocked_id: &Uuid) -> Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO blocks (blocker_id, blocked_id)
+            VALUES ($1, $2)
+            ON CONFLICT (blocker_id, blocked_id) DO NOTHING
+            "#,
+            blocker_id,
+            blocked_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM follows
+            WHERE (follower_id = $1 AND followee_id = $2)
+                OR (follower_id = $2 AND followee_id = $1)
+            "#,
+            blocker_id,
+            blocked_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn unblock(pool: &PgPool, blocker_id: &Uuid, blocked_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM blocks
+            WHERE blocker_id = $1 AND blocked_id = $2
+            "#,
+            blocker_id,
+            blocked_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn fetch_blocked(pool: &PgPool, blocker_id: &Uuid, page: &Page) -> Result<Vec<BlockedUser>> {
+        let blocked = sqlx::query_as!(
+            BlockedUser,
+            r#"
+            SELECT u.id, u.name, u.username, u.avatar_url, b.created_at AS since
+            FROM blocks b
+            JOIN users u ON u.id = b.blocked_id
+            WHERE b.blocker_id = $1 AND u.deleted_at IS NULL
+            ORDER BY b.created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            blocker_id,
+            page.limit,
+            page.offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(blocked)
+    }
+
+    pub async fn is_blocked(pool: &PgPool, blocker_id: &Uuid, blocked_id: &Uuid) -> Result<bool> {
+        let blocked = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM blocks
+                WHERE blocker_id = $1 AND blocked_id = $2
+            ) AS "blocked!"
+            "#,
+            blocker_id,
+            blocked_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(blocked)
+    }
+
+    // everyone `blocker_id` doesn't want to hear from
+    pub async fn fetch_blocked_ids(pool: &PgPool, blocker_id: &Uuid) -> Result<Vec<Uuid>> {
+        let blocked = sqlx::query_scalar!(
+            r#"
+            SELECT blocked_id
+            FROM blocks
+            WHERE blocker_id = $1
+            "#,
+            blocker_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(blocked)
+    }
+}
@@ -0,0 +1,54 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, NotFoundError, ValidationError},
+    modules::{
+        block::{model::BlockedUser, repository::BlockRepo},
+        follow::model::Page,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct BlockService {
+    pool: PgPool,
+}
+
+impl BlockService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn block(&self, blocker_id: &Uuid, blocked_id: &Uuid) -> Result<(), AppError> {
+        if blocker_id == blocked_id {
+            return Err(AppError::Validation(ValidationError::CannotBlockSelf));
+        }
+
+        BlockRepo::block(&self.pool, blocker_id, blocked_id).await?;
+
+        Ok(())
+    }
+
+    pub async fn unblock(&self, blocker_id: &Uuid, blocked_id: &Uuid) -> Result<(), AppError> {
+        if !BlockRepo::unblock(&self.pool, blocker_id, blocked_id).await? {
+            return Err(AppError::NotFound(NotFoundError::BlockNotFound));
+        }
+
+        Ok(())
+    }
+
+    pub async fn blocked(&self, blocker_id: &Uuid, page: Page) -> Result<Vec<BlockedUser>, AppError> {
+        let blocked = BlockRepo::fetch_blocked(&self.pool, blocker_id, &page).await?;
+
+        Ok(blocked)
+    }
+
+    // for anything that reaches a user directly, a blocked sender is told the user doesn't exist
+    pub async fn ensure_not_blocked(&self, sender_id: &Uuid, recipient_id: &Uuid) -> Result<(), AppError> {
+        if BlockRepo::is_blocked(&self.pool, recipient_id, sender_id).await? {
+            return Err(AppError::NotFound(NotFoundError::UserNotFound));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    modules::{export::model::DataExportResponse, user::model::UserId},
+    state::AppState,
+};
+
+pub async fn request_export_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+) -> Result<(StatusCode, Json<ApiResponse<impl serde::Serialize>>), AppError> {
+    let export = state.export_service.request(user_id.0).await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ApiResponse::success(
+            "Export started, check its status for the download link",
+            DataExportResponse::from(export),
+        )),
+    ))
+}
+
+pub async fn export_status_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(export_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let export = state.export_service.status(&user_id.0, &export_id).await?;
+
+    Ok(Json(ApiResponse::success(
+        "fetch export successfuly",
+        DataExportResponse::from(export),
+    )))
+}
+
+pub async fn download_export_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(export_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let archive = state.export_service.download(&user_id.0, &export_id).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"wiki-export-{export_id}.zip\""),
+            ),
+        ],
+        archive,
+    ))
+}
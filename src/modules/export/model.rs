@@ -0,0 +1,263 @@
+use std::fmt::Write;
+
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+use time::{Date, OffsetDateTime, Time};
+use uuid::Uuid;
+
+use crate::modules::{
+    privacy::model::PrivacySettings,
+    todo::model::{Priority, due_date, due_time},
+    user::model::UserResponseDto,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum ExportStatus {
+    Pending,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, FromRow)]
+pub struct DataExport {
+    pub id: Uuid,
+    pub status: ExportStatus,
+    pub file_path: Option<String>,
+    pub created_at: OffsetDateTime,
+    pub completed_at: Option<OffsetDateTime>,
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl DataExport {
+    pub fn is_downloadable(&self) -> bool {
+        self.status == ExportStatus::Ready
+            && self.expires_at.is_some_and(|expires_at| expires_at > OffsetDateTime::now_utc())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataExportResponse {
+    pub id: Uuid,
+    pub status: ExportStatus,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub completed_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+    // only while the archive can still be downloaded
+    pub download_url: Option<String>,
+}
+
+impl From<DataExport> for DataExportResponse {
+    fn from(export: DataExport) -> Self {
+        let download_url = export
+            .is_downloadable()
+            .then(|| format!("/api/user/me/export/{}/download", export.id));
+
+        Self {
+            id: export.id,
+            status: export.status,
+            created_at: export.created_at,
+            completed_at: export.completed_at,
+            expires_at: export.expires_at,
+            download_url,
+        }
+    }
+}
+
+// everything below ends up in export.json, and rendered again in export.md
+#[derive(Serialize)]
+pub struct ExportArchive {
+    #[serde(with = "time::serde::rfc3339")]
+    pub generated_at: OffsetDateTime,
+    pub profile: UserResponseDto,
+    pub privacy: PrivacySettings,
+    pub tags: Vec<ExportTag>,
+    pub categories: Vec<ExportCategory>,
+    pub todos: Vec<ExportTodo>,
+    pub daily_progress: Vec<ExportDailyProgress>,
+    pub rooms: Vec<ExportRoomMembership>,
+    pub messages: Vec<ExportMessage>,
+    pub notifications: Vec<ExportNotification>,
+}
+
+impl ExportArchive {
+    // the same data as export.json, for people rather than programs
+    pub fn to_markdown(&self) -> Result<String, std::fmt::Error> {
+        let mut md = String::new();
+        let profile = &self.profile;
+
+        writeln!(md, "# Wiki data export for @{}\n", profile.username)?;
+        writeln!(md, "Generated at {}\n", format_time(self.generated_at))?;
+
+        writeln!(md, "## Profile\n")?;
+        writeln!(md, "- Name: {}", profile.name)?;
+        writeln!(md, "- Username: {}", profile.username)?;
+        writeln!(md, "- Email: {}", profile.email)?;
+        writeln!(md, "- Bio: {}", profile.bio.as_deref().unwrap_or("-"))?;
+        writeln!(md, "- Avatar: {}", profile.avatar_url.as_deref().unwrap_or("-"))?;
+        writeln!(md, "- Links: {}\n", if profile.links.is_empty() { "-".to_string() } else { profile.links.join(", ") })?;
+
+        writeln!(md, "## Privacy\n")?;
+        writeln!(md, "- Profile: {}", self.privacy.profile.as_str())?;
+        writeln!(md, "- Daily progress: {}", self.privacy.daily_progress.as_str())?;
+        writeln!(md, "- Stats: {}", self.privacy.stats.as_str())?;
+        writeln!(md, "- Room memberships: {}", self.privacy.room_memberships.as_str())?;
+        writeln!(md, "- Followers: {}\n", self.privacy.followers.as_str())?;
+
+        writeln!(md, "## Tags ({})\n", self.tags.len())?;
+        for tag in &self.tags {
+            writeln!(md, "- {} (`{}`)", tag.name, tag.slug)?;
+        }
+
+        writeln!(md, "\n## Categories ({})\n", self.categories.len())?;
+        for category in &self.categories {
+            writeln!(md, "- {} (`{}`)", category.name, category.slug)?;
+        }
+
+        writeln!(md, "\n## Todos ({})\n", self.todos.len())?;
+        for todo in &self.todos {
+            writeln!(md, "### {}\n", todo.title)?;
+            writeln!(md, "{}\n", todo.description)?;
+            writeln!(md, "- Category: {}", todo.category)?;
+            if !todo.tags.is_empty() {
+                writeln!(md, "- Tags: {}", todo.tags.join(", "))?;
+            }
+            writeln!(md, "- Priority: {}", todo.priority.as_str())?;
+            match (todo.due_on, todo.due_time) {
+                (Some(day), Some(time)) => writeln!(md, "- Due: {} {:02}:{:02}", day, time.hour(), time.minute())?,
+                (Some(day), None) => writeln!(md, "- Due: {}", day)?,
+                _ => {}
+            }
+            if let Some(minutes) = todo.estimated_minutes {
+                writeln!(md, "- Estimate: {} min", minutes)?;
+            }
+            writeln!(md, "- Created: {}\n", format_time(todo.created_at))?;
+        }
+
+        writeln!(md, "## Daily progress ({} days)\n", self.daily_progress.len())?;
+        for progress in &self.daily_progress {
+            writeln!(md, "### {}\n", progress.day)?;
+            for todo in &progress.todos {
+                writeln!(md, "- [{}] {}", if todo.is_done { "x" } else { " " }, todo.title)?;
+            }
+            writeln!(md)?;
+        }
+
+        writeln!(md, "## Rooms ({})\n", self.rooms.len())?;
+        for room in &self.rooms {
+            let role = if room.is_owner { "owner" } else { "member" };
+            writeln!(md, "- {} ({role})", room.name)?;
+        }
+
+        writeln!(md, "\n## Messages ({})\n", self.messages.len())?;
+        for message in &self.messages {
+            writeln!(md, "- {} in {}: {}", format_time(message.created_at), message.room_name, message.content)?;
+        }
+
+        writeln!(md, "\n## Notifications ({})\n", self.notifications.len())?;
+        for notification in &self.notifications {
+            writeln!(md, "- [{}] {}{}", notification.kind, notification.title, notification.body.as_deref().map(|body| format!(": {body}")).unwrap_or_default())?;
+        }
+
+        Ok(md)
+    }
+}
+
+fn format_time(time: OffsetDateTime) -> String {
+    format!("{} {:02}:{:02} UTC", time.date(), time.hour(), time.minute())
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct ExportTag {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct ExportCategory {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct ExportTodo {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub category: String,
+    pub tags: Vec<String>,
+    pub priority: Priority,
+    #[serde(with = "due_date::option")]
+    pub due_on: Option<Date>,
+    #[serde(with = "due_time::option")]
+    pub due_time: Option<Time>,
+    pub estimated_minutes: Option<i32>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ExportProgressDay {
+    pub id: Uuid,
+    pub day: Date,
+}
+
+#[derive(Debug, FromRow)]
+pub struct ExportProgressTodoRow {
+    pub daily_progress_id: Uuid,
+    pub todo_id: Uuid,
+    pub title: String,
+    pub is_done: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportDailyProgress {
+    pub id: Uuid,
+    pub day: String,
+    pub todos: Vec<ExportProgressTodo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportProgressTodo {
+    pub todo_id: Uuid,
+    pub title: String,
+    pub is_done: bool,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct ExportRoomMembership {
+    pub room_id: Uuid,
+    pub name: String,
+    pub is_owner: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub joined_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct ExportMessage {
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub room_name: String,
+    pub content: String,
+    pub parent_id: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct ExportNotification {
+    pub id: Uuid,
+    pub kind: String,
+    pub title: String,
+    pub body: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+}
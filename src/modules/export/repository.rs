@@ -0,0 +1,293 @@
+use sqlx::{PgPool, Result};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::{
+    export::model::{
+        DataExport, ExportCategory, ExportMessage, ExportNotification, ExportProgressDay,
+        ExportProgressTodoRow, ExportRoomMembership, ExportStatus, ExportTag, ExportTodo,
+    },
+    todo::model::Priority,
+};
+
+pub struct ExportRepo;
+
+impl ExportRepo {
+    pub async fn create(pool: &PgPool, user_id: &Uuid) -> Result<DataExport> {
+        let export = sqlx::query_as!(
+            DataExport,
+            r#"
+            INSERT INTO data_exports (user_id)
+            VALUES ($1)
+            RETURNING id, status AS "status: ExportStatus", file_path, created_at, completed_at, expires_at
+            "#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(export)
+    }
+
+    pub async fn fetch_pending(pool: &PgPool, user_id: &Uuid) -> Result<Option<DataExport>> {
+        let export = sqlx::query_as!(
+            DataExport,
+            r#"
+            SELECT id, status AS "status: ExportStatus", file_path, created_at, completed_at, expires_at
+            FROM data_exports
+            WHERE user_id = $1 AND status = 'pending'
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(export)
+    }
+
+    pub async fn fetch(pool: &PgPool, export_id: &Uuid, user_id: &Uuid) -> Result<Option<DataExport>> {
+        let export = sqlx::query_as!(
+            DataExport,
+            r#"
+            SELECT id, status AS "status: ExportStatus", file_path, created_at, completed_at, expires_at
+            FROM data_exports
+            WHERE id = $1 AND user_id = $2
+            "#,
+            export_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(export)
+    }
+
+    pub async fn mark_ready(
+        pool: &PgPool,
+        export_id: &Uuid,
+        file_path: &str,
+        expires_at: OffsetDateTime,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE data_exports
+            SET status = 'ready', file_path = $1, completed_at = now(), expires_at = $2
+            WHERE id = $3
+            "#,
+            file_path,
+            expires_at,
+            export_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn mark_failed(pool: &PgPool, export_id: &Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE data_exports
+            SET status = 'failed', completed_at = now()
+            WHERE id = $1
+            "#,
+            export_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // also picks up exports that never finished, e.g. because the server restarted mid build
+    // returns the files that should be removed from disk
+    pub async fn delete_expired(pool: &PgPool, pending_timeout_secs: f64) -> Result<Vec<String>> {
+        let files = sqlx::query_scalar!(
+            r#"
+            DELETE FROM data_exports
+            WHERE expires_at < now()
+                OR (status = 'pending' AND created_at < now() - make_interval(secs => $1))
+            RETURNING file_path
+            "#,
+            pending_timeout_secs
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(files.into_iter().flatten().collect())
+    }
+
+    pub async fn delete_for_user(pool: &PgPool, user_id: &Uuid) -> Result<Vec<String>> {
+        let files = sqlx::query_scalar!(
+            r#"
+            DELETE FROM data_exports
+            WHERE user_id = $1
+            RETURNING file_path
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(files.into_iter().flatten().collect())
+    }
+
+    pub async fn fetch_tags(pool: &PgPool, user_id: &Uuid) -> Result<Vec<ExportTag>> {
+        let tags = sqlx::query_as!(
+            ExportTag,
+            r#"
+            SELECT id, name, slug
+            FROM tags
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    pub async fn fetch_categories(pool: &PgPool, user_id: &Uuid) -> Result<Vec<ExportCategory>> {
+        let categories = sqlx::query_as!(
+            ExportCategory,
+            r#"
+            SELECT id, name, slug
+            FROM categories
+            WHERE user_id = $1
+            ORDER BY name
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(categories)
+    }
+
+    // todo timestamps are stored without a zone, they are written in UTC
+    pub async fn fetch_todos(pool: &PgPool, user_id: &Uuid) -> Result<Vec<ExportTodo>> {
+        let todos = sqlx::query_as!(
+            ExportTodo,
+            r#"
+            SELECT t.id, t.title, t.description, c.name AS category,
+                COALESCE(
+                    (SELECT array_agg(tg.name ORDER BY tg.name)
+                    FROM tag_todo tt
+                    JOIN tags tg ON tg.id = tt.tag_id
+                    WHERE tt.todo_id = t.id),
+                    '{}'
+                ) AS "tags!",
+                t.priority AS "priority: Priority", t.due_on, t.due_time, t.estimated_minutes,
+                t.created_at AT TIME ZONE 'UTC' AS "created_at!",
+                t.updated_at AT TIME ZONE 'UTC' AS "updated_at!"
+            FROM todos t
+            JOIN categories c ON c.id = t.category_id
+            WHERE t.user_id = $1
+            ORDER BY t.created_at
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(todos)
+    }
+
+    pub async fn fetch_progress_days(pool: &PgPool, user_id: &Uuid) -> Result<Vec<ExportProgressDay>> {
+        let days = sqlx::query_as!(
+            ExportProgressDay,
+            r#"
+            SELECT id, day
+            FROM daily_progress
+            WHERE user_id = $1
+            ORDER BY day
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(days)
+    }
+
+    pub async fn fetch_progress_todos(
+        pool: &PgPool,
+        user_id: &Uuid,
+    ) -> Result<Vec<ExportProgressTodoRow>> {
+        let todos = sqlx::query_as!(
+            ExportProgressTodoRow,
+            r#"
+            SELECT pt.daily_progress_id, pt.todo_id, t.title, pt.is_done
+            FROM daily_progress_todos pt
+            JOIN daily_progress p ON p.id = pt.daily_progress_id
+            JOIN todos t ON t.id = pt.todo_id
+            WHERE p.user_id = $1
+            ORDER BY pt.created_at
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(todos)
+    }
+
+    // joined rooms and owned rooms, an owner doesn't have to be a member
+    pub async fn fetch_rooms(pool: &PgPool, user_id: &Uuid) -> Result<Vec<ExportRoomMembership>> {
+        let rooms = sqlx::query_as!(
+            ExportRoomMembership,
+            r#"
+            SELECT r.id AS room_id, r.name, r.owner_id = $1 AS "is_owner!", m.joined_at AS "joined_at?"
+            FROM rooms r
+            LEFT JOIN members m ON m.room_id = r.id AND m.user_id = $1
+            WHERE m.user_id = $1 OR r.owner_id = $1
+            ORDER BY r.name
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rooms)
+    }
+
+    pub async fn fetch_messages(pool: &PgPool, user_id: &Uuid) -> Result<Vec<ExportMessage>> {
+        let messages = sqlx::query_as!(
+            ExportMessage,
+            r#"
+            SELECT m.id, m.room_id, r.name AS room_name, m.content, m.parent_id, m.created_at
+            FROM user_messages m
+            JOIN rooms r ON r.id = m.room_id
+            WHERE m.user_id = $1
+            ORDER BY m.created_at
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    pub async fn fetch_notifications(pool: &PgPool, user_id: &Uuid) -> Result<Vec<ExportNotification>> {
+        let notifications = sqlx::query_as!(
+            ExportNotification,
+            r#"
+            SELECT id, type::text AS "kind!", title, body,
+                created_at AT TIME ZONE 'UTC' AS created_at
+            FROM notifications
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(notifications)
+    }
+}
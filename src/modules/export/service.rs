@@ -0,0 +1,195 @@
+use std::{collections::HashMap, io::Write, path::PathBuf};
+
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    common::error::{AppError, NotFoundError, ValidationError},
+    modules::{
+        export::{
+            model::{
+                DataExport, ExportArchive, ExportDailyProgress, ExportProgressTodo, ExportStatus,
+            },
+            repository::ExportRepo,
+        },
+        privacy::repository::PrivacyRepo,
+        user::repository::UserRepo,
+    },
+};
+
+const EXPORT_TTL_HOURS: i64 = 24;
+// a build still pending after this is treated as lost
+const PENDING_TIMEOUT_SECS: f64 = 60.0 * 60.0;
+
+#[derive(Debug, Clone)]
+pub struct ExportService {
+    pool: PgPool,
+    dir: PathBuf,
+}
+
+impl ExportService {
+    pub fn new(pool: PgPool, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            pool,
+            dir: dir.into(),
+        }
+    }
+
+    // builds in the background, asking again while a build is running returns that one
+    pub async fn request(&self, user_id: Uuid) -> Result<DataExport, AppError> {
+        if let Some(pending) = ExportRepo::fetch_pending(&self.pool, &user_id).await? {
+            return Ok(pending);
+        }
+
+        let export = ExportRepo::create(&self.pool, &user_id).await?;
+
+        let service = self.clone();
+        let export_id = export.id;
+        tokio::spawn(async move {
+            if let Err(e) = service.build(&export_id, &user_id).await {
+                eprintln!("data export {export_id} failed: {e}");
+                let _ = ExportRepo::mark_failed(&service.pool, &export_id).await;
+            }
+        });
+
+        Ok(export)
+    }
+
+    pub async fn status(&self, user_id: &Uuid, export_id: &Uuid) -> Result<DataExport, AppError> {
+        let export = ExportRepo::fetch(&self.pool, export_id, user_id)
+            .await?
+            .ok_or(AppError::NotFound(NotFoundError::ExportNotFound))?;
+
+        Ok(export)
+    }
+
+    pub async fn download(&self, user_id: &Uuid, export_id: &Uuid) -> Result<Vec<u8>, AppError> {
+        let export = self.status(user_id, export_id).await?;
+
+        if export.status == ExportStatus::Pending {
+            return Err(AppError::Validation(ValidationError::ExportNotReady));
+        }
+
+        let Some(path) = export.file_path.as_ref().filter(|_| export.is_downloadable()) else {
+            return Err(AppError::NotFound(NotFoundError::ExportNotFound));
+        };
+
+        tokio::fs::read(path)
+            .await
+            .map_err(|_| AppError::NotFound(NotFoundError::ExportNotFound))
+    }
+
+    pub async fn cleanup_expired(&self) -> Result<(), AppError> {
+        for path in ExportRepo::delete_expired(&self.pool, PENDING_TIMEOUT_SECS).await? {
+            remove_archive(&path).await;
+        }
+
+        Ok(())
+    }
+
+    // for erasing an account, the rows would go with the user but the archives would stay on disk
+    pub async fn delete_for_user(&self, user_id: &Uuid) -> Result<(), AppError> {
+        for path in ExportRepo::delete_for_user(&self.pool, user_id).await? {
+            remove_archive(&path).await;
+        }
+
+        Ok(())
+    }
+
+    async fn build(&self, export_id: &Uuid, user_id: &Uuid) -> Result<(), AppError> {
+        let archive = self.collect(user_id).await?;
+
+        let json = serde_json::to_vec_pretty(&archive)
+            .map_err(|e| AppError::Failed(format!("Failed to serialize export: {e}")))?;
+        let markdown = archive
+            .to_markdown()
+            .map_err(|e| AppError::Failed(format!("Failed to render export: {e}")))?;
+
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| AppError::Failed(format!("Failed to create export dir: {e}")))?;
+
+        let path = self.dir.join(format!("{export_id}.zip"));
+        write_zip(path.clone(), json, markdown).await?;
+
+        let expires_at = OffsetDateTime::now_utc() + Duration::hours(EXPORT_TTL_HOURS);
+        let path = path.to_string_lossy();
+
+        // the export was deleted while building, so nothing would ever clean the file up
+        if !ExportRepo::mark_ready(&self.pool, export_id, &path, expires_at).await? {
+            remove_archive(&path).await;
+        }
+
+        Ok(())
+    }
+
+    async fn collect(&self, user_id: &Uuid) -> Result<ExportArchive, AppError> {
+        let profile = UserRepo::fetch_by_id(&self.pool, *user_id)
+            .await?
+            .ok_or(AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        let mut progress_todos: HashMap<Uuid, Vec<ExportProgressTodo>> = HashMap::new();
+        for row in ExportRepo::fetch_progress_todos(&self.pool, user_id).await? {
+            progress_todos
+                .entry(row.daily_progress_id)
+                .or_default()
+                .push(ExportProgressTodo {
+                    todo_id: row.todo_id,
+                    title: row.title,
+                    is_done: row.is_done,
+                });
+        }
+
+        let daily_progress = ExportRepo::fetch_progress_days(&self.pool, user_id)
+            .await?
+            .into_iter()
+            .map(|day| ExportDailyProgress {
+                id: day.id,
+                day: day.day.to_string(),
+                todos: progress_todos.remove(&day.id).unwrap_or_default(),
+            })
+            .collect();
+
+        Ok(ExportArchive {
+            generated_at: OffsetDateTime::now_utc(),
+            profile,
+            privacy: PrivacyRepo::fetch(&self.pool, user_id).await?.unwrap_or_default(),
+            tags: ExportRepo::fetch_tags(&self.pool, user_id).await?,
+            categories: ExportRepo::fetch_categories(&self.pool, user_id).await?,
+            todos: ExportRepo::fetch_todos(&self.pool, user_id).await?,
+            daily_progress,
+            rooms: ExportRepo::fetch_rooms(&self.pool, user_id).await?,
+            messages: ExportRepo::fetch_messages(&self.pool, user_id).await?,
+            notifications: ExportRepo::fetch_notifications(&self.pool, user_id).await?,
+        })
+    }
+}
+
+async fn write_zip(path: PathBuf, json: Vec<u8>, markdown: String) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::create(&path)?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        zip.start_file("export.json", options)?;
+        zip.write_all(&json)?;
+        zip.start_file("export.md", options)?;
+        zip.write_all(markdown.as_bytes())?;
+        zip.finish()?;
+
+        Ok::<_, zip::result::ZipError>(())
+    })
+    .await
+    .map_err(|_| AppError::Failed("Failed to write export".into()))?
+    .map_err(|e| AppError::Failed(format!("Failed to write export: {e}")))
+}
+
+async fn remove_archive(path: &str) {
+    if let Err(e) = tokio::fs::remove_file(path).await
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        eprintln!("failed to remove export {path}: {e}");
+    }
+}
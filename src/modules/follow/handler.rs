@@ -0,0 +1,124 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+};
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    modules::{
+        follow::model::{FollowResponse, PageDto},
+        privacy::model::Section,
+        user::model::UserId,
+    },
+    state::AppState,
+};
+
+pub async fn follow_user_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(username): Path<String>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let followee = state.user_service.get_user_by_username(&username).await?;
+
+    state.block_service.ensure_not_blocked(&user_id.0, &followee.id).await?;
+
+    let status = state.follow_service.follow(&user_id.0, &followee).await?;
+
+    Ok(Json(ApiResponse::success(
+        "follow successfuly",
+        FollowResponse {
+            username: followee.username,
+            status: Some(status),
+        },
+    )))
+}
+
+pub async fn unfollow_user_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(username): Path<String>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let followee = state.user_service.get_user_by_username(&username).await?;
+
+    state.follow_service.unfollow(&user_id.0, &followee.id).await?;
+
+    Ok(Json(ApiResponse::success(
+        "unfollow successfuly",
+        FollowResponse {
+            username: followee.username,
+            status: None,
+        },
+    )))
+}
+
+pub async fn followers_handler(
+    State(state): State<AppState>,
+    user_id: Option<Extension<UserId>>,
+    Path(username): Path<String>,
+    Query(page): Query<PageDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let user = state.user_service.get_user_by_username(&username).await?;
+
+    state
+        .privacy_service
+        .audience(user_id.map(|Extension(user_id)| user_id.0), &user.id)
+        .await?
+        .ensure(Section::Followers)?;
+
+    let followers = state.follow_service.followers(&user.id, page.into()).await?;
+
+    Ok(Json(ApiResponse::success("fetch followers successfuly", followers)))
+}
+
+pub async fn following_handler(
+    State(state): State<AppState>,
+    user_id: Option<Extension<UserId>>,
+    Path(username): Path<String>,
+    Query(page): Query<PageDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let user = state.user_service.get_user_by_username(&username).await?;
+
+    state
+        .privacy_service
+        .audience(user_id.map(|Extension(user_id)| user_id.0), &user.id)
+        .await?
+        .ensure(Section::Followers)?;
+
+    let following = state.follow_service.following(&user.id, page.into()).await?;
+
+    Ok(Json(ApiResponse::success("fetch following successfuly", following)))
+}
+
+pub async fn follow_requests_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Query(page): Query<PageDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let requests = state.follow_service.requests(&user_id.0, page.into()).await?;
+
+    Ok(Json(ApiResponse::success("fetch follow requests successfuly", requests)))
+}
+
+pub async fn accept_follow_request_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(username): Path<String>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let follower = state.user_service.get_user_by_username(&username).await?;
+
+    state.follow_service.accept(&user_id.0, &follower.id).await?;
+
+    Ok(Json(ApiResponse::success("follow request accepted successfuly", None::<()>)))
+}
+
+pub async fn reject_follow_request_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(username): Path<String>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let follower = state.user_service.get_user_by_username(&username).await?;
+
+    state.follow_service.reject(&user_id.0, &follower.id).await?;
+
+    Ok(Json(ApiResponse::success("follow request rejected successfuly", None::<()>)))
+}
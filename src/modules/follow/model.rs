@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum FollowStatus {
+    Pending,
+    Accepted,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FollowResponse {
+    pub username: String,
+    // None once unfollowed
+    pub status: Option<FollowStatus>,
+}
+
+// an entry in a followers, following or follow request list
+#[derive(Debug, FromRow, Serialize)]
+pub struct FollowUser {
+    pub id: Uuid,
+    pub name: String,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub since: OffsetDateTime,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct FollowCounts {
+    pub followers: i64,
+    pub following: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PageDto {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub struct Page {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl From<PageDto> for Page {
+    fn from(value: PageDto) -> Self {
+        Self {
+            limit: value.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+            offset: value.offset.unwrap_or(0).max(0),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+use crate::modules::follow::model::{FollowCounts, FollowStatus, FollowUser, Page};
+
+pub struct FollowRepo;
+
+impl FollowRepo {
+    // following again keeps the existing row, a pending request stays pending
+    pub async fn follow(
+        pool: &PgPool,
+        follower_id: &Uuid,
+        followee_id: &Uuid,
+        status: FollowStatus,
+    ) -> Result<FollowStatus> {
+        let status = sqlx::query_scalar!(
+            r#"
+            INSERT INTO follows (follower_id, followee_id, status, accepted_at)
+            VALUES ($1, $2, $3, CASE WHEN $3 = 'accepted' THEN now() END)
+            ON CONFLICT (follower_id, followee_id) DO UPDATE SET status = follows.status
+            RETURNING status AS "status: FollowStatus"
+            "#,
+            follower_id,
+            followee_id,
+            status as FollowStatus
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(status)
+    }
+
+    // also withdraws a pending request, false when there was nothing to remove
+    pub async fn unfollow(pool: &PgPool, follower_id: &Uuid, followee_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM follows
+            WHERE follower_id = $1 AND followee_id = $2
+            "#,
+            follower_id,
+            followee_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn fetch_status(
+        pool: &PgPool,
+        follower_id: &Uuid,
+        followee_id: &Uuid,
+    ) -> Result<Option<FollowStatus>> {
+        let status = sqlx::query_scalar!(
+            r#"
+            SELECT status AS "status: FollowStatus"
+            FROM follows
+            WHERE follower_id = $1 AND followee_id = $2
+            "#,
+            follower_id,
+            followee_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(status)
+    }
+
+    pub async fn fetch_followers(pool: &PgPool, user_id: &Uuid, page: &Page) -> Result<Vec<FollowUser>> {
+        let followers = sqlx::query_as!(
+            FollowUser,
+            r#"
+            SELECT u.id, u.name, u.username, u.avatar_url, f.accepted_at AS "since!"
+            FROM follows f
+            JOIN users u ON u.id = f.follower_id
+            WHERE f.followee_id = $1 AND f.status = 'accepted' AND u.deleted_at IS NULL
+            ORDER BY f.accepted_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            page.limit,
+            page.offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(followers)
+    }
+
+    pub async fn fetch_following(pool: &PgPool, user_id: &Uuid, page: &Page) -> Result<Vec<FollowUser>> {
+        let following = sqlx::query_as!(
+            FollowUser,
+            r#"
+            SELECT u.id, u.name, u.username, u.avatar_url, f.accepted_at AS "since!"
+            FROM follows f
+            JOIN users u ON u.id = f.followee_id
+            WHERE f.follower_id = $1 AND f.status = 'accepted' AND u.deleted_at IS NULL
+            ORDER BY f.accepted_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            page.limit,
+            page.offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(following)
+    }
+
+    pub async fn fetch_requests(pool: &PgPool, user_id: &Uuid, page: &Page) -> Result<Vec<FollowUser>> {
+        let requests = sqlx::query_as!(
+            FollowUser,
+            r#"
+            SELECT u.id, u.name, u.username, u.avatar_url, f.created_at AS since
+            FROM follows f
+            JOIN users u ON u.id = f.follower_id
+            WHERE f.followee_id = $1 AND f.status = 'pending' AND u.deleted_at IS NULL
+            ORDER BY f.created_at
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            page.limit,
+            page.offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(requests)
+    }
+
+    pub async fn fetch_counts(pool: &PgPool, user_id: &Uuid) -> Result<FollowCounts> {
+        let counts = sqlx::query_as!(
+            FollowCounts,
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE f.followee_id = $1) AS "followers!",
+                COUNT(*) FILTER (WHERE f.follower_id = $1) AS "following!"
+            FROM follows f
+            JOIN users u ON u.id = CASE WHEN f.followee_id = $1 THEN f.follower_id ELSE f.followee_id END
+            WHERE (f.followee_id = $1 OR f.follower_id = $1)
+                AND f.status = 'accepted'
+                AND u.deleted_at IS NULL
+            "#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    // false when there was no pending request from `follower_id`
+    pub async fn accept(pool: &PgPool, followee_id: &Uuid, follower_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE follows
+            SET status = 'accepted', accepted_at = now()
+            WHERE followee_id = $1 AND follower_id = $2 AND status = 'pending'
+            "#,
+            followee_id,
+            follower_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn reject(pool: &PgPool, followee_id: &Uuid, follower_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM follows
+            WHERE followee_id = $1 AND follower_id = $2 AND status = 'pending'
+            "#,
+            followee_id,
+            follower_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn accept_all(pool: &PgPool, followee_id: &Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE follows
+            SET status = 'accepted', accepted_at = now()
+            WHERE followee_id = $1 AND status = 'pending'
+            "#,
+            followee_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
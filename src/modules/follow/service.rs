@@ -0,0 +1,102 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, NotFoundError, ValidationError},
+    modules::{
+        block::repository::BlockRepo,
+        follow::{
+            model::{FollowCounts, FollowStatus, FollowUser, Page},
+            repository::FollowRepo,
+        },
+        privacy::{model::Visibility, repository::PrivacyRepo},
+        user::model::User,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct FollowService {
+    pool: PgPool,
+}
+
+impl FollowService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // public profiles are followed right away, the rest get a request to approve
+    pub async fn follow(&self, follower_id: &Uuid, followee: &User) -> Result<FollowStatus, AppError> {
+        if *follower_id == followee.id {
+            return Err(AppError::Validation(ValidationError::CannotFollowSelf));
+        }
+
+        if BlockRepo::is_blocked(&self.pool, follower_id, &followee.id).await? {
+            return Err(AppError::Validation(ValidationError::UserBlocked));
+        }
+
+        let settings = PrivacyRepo::fetch(&self.pool, &followee.id).await?.unwrap_or_default();
+
+        let status = if settings.profile == Visibility::Public {
+            FollowStatus::Accepted
+        } else {
+            FollowStatus::Pending
+        };
+
+        let status = FollowRepo::follow(&self.pool, follower_id, &followee.id, status).await?;
+
+        Ok(status)
+    }
+
+    pub async fn unfollow(&self, follower_id: &Uuid, followee_id: &Uuid) -> Result<(), AppError> {
+        FollowRepo::unfollow(&self.pool, follower_id, followee_id).await?;
+
+        Ok(())
+    }
+
+    pub async fn counts(&self, user_id: &Uuid) -> Result<FollowCounts, AppError> {
+        let counts = FollowRepo::fetch_counts(&self.pool, user_id).await?;
+
+        Ok(counts)
+    }
+
+    pub async fn followers(&self, user_id: &Uuid, page: Page) -> Result<Vec<FollowUser>, AppError> {
+        let followers = FollowRepo::fetch_followers(&self.pool, user_id, &page).await?;
+
+        Ok(followers)
+    }
+
+    pub async fn following(&self, user_id: &Uuid, page: Page) -> Result<Vec<FollowUser>, AppError> {
+        let following = FollowRepo::fetch_following(&self.pool, user_id, &page).await?;
+
+        Ok(following)
+    }
+
+    pub async fn requests(&self, user_id: &Uuid, page: Page) -> Result<Vec<FollowUser>, AppError> {
+        let requests = FollowRepo::fetch_requests(&self.pool, user_id, &page).await?;
+
+        Ok(requests)
+    }
+
+    pub async fn accept(&self, followee_id: &Uuid, follower_id: &Uuid) -> Result<(), AppError> {
+        if !FollowRepo::accept(&self.pool, followee_id, follower_id).await? {
+            return Err(AppError::NotFound(NotFoundError::FollowRequestNotFound));
+        }
+
+        Ok(())
+    }
+
+    pub async fn reject(&self, followee_id: &Uuid, follower_id: &Uuid) -> Result<(), AppError> {
+        if !FollowRepo::reject(&self.pool, followee_id, follower_id).await? {
+            return Err(AppError::NotFound(NotFoundError::FollowRequestNotFound));
+        }
+
+        Ok(())
+    }
+
+    // once an account goes public there is nothing left to approve
+    pub async fn accept_all(&self, followee_id: &Uuid) -> Result<(), AppError> {
+        FollowRepo::accept_all(&self.pool, followee_id).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use time::{Duration, OffsetDateTime};
+
+// after `threshold` failures inside `window` the key is locked, doubling each further failure
+pub struct ThrottlePolicy {
+    pub threshold: i32,
+    pub window: Duration,
+    pub base_lock: Duration,
+    pub max_lock: Duration,
+}
+
+pub const ACCOUNT_POLICY: ThrottlePolicy = ThrottlePolicy {
+    threshold: 5,
+    window: Duration::hours(1),
+    base_lock: Duration::minutes(1),
+    max_lock: Duration::hours(1),
+};
+
+pub const IP_POLICY: ThrottlePolicy = ThrottlePolicy {
+    threshold: 20,
+    window: Duration::minutes(15),
+    base_lock: Duration::minutes(5),
+    max_lock: Duration::hours(1),
+};
+
+impl ThrottlePolicy {
+    pub fn lock_until(&self, failed_count: i32) -> Option<OffsetDateTime> {
+        if failed_count < self.threshold {
+            return None;
+        }
+
+        let doublings = (failed_count - self.threshold).min(16) as u32;
+        let lock = (self.base_lock * 2_i32.pow(doublings)).min(self.max_lock);
+
+        Some(OffsetDateTime::now_utc() + lock)
+    }
+}
+
+pub fn email_key(email: &str) -> String {
+    format!("email:{}", email.to_lowercase())
+}
+
+pub fn ip_key(ip: &str) -> String {
+    format!("ip:{ip}")
+}
@@ -0,0 +1,75 @@
+use sqlx::{PgPool, Result};
+use time::OffsetDateTime;
+
+pub struct LockoutRepo;
+
+impl LockoutRepo {
+    pub async fn is_locked(pool: &PgPool, keys: &[String]) -> Result<bool> {
+        let locked = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM login_throttle
+                WHERE key = ANY($1) AND locked_until > now()
+            ) AS "locked!"
+            "#,
+            keys
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(locked)
+    }
+
+    // the counter starts over once the key has been quiet for `window_seconds`
+    pub async fn record_failure(pool: &PgPool, key: &str, window_seconds: f64) -> Result<i32> {
+        let failed_count = sqlx::query_scalar!(
+            r#"
+            INSERT INTO login_throttle (key, failed_count, last_failed_at)
+            VALUES ($1, 1, now())
+            ON CONFLICT (key) DO UPDATE
+            SET failed_count = CASE
+                    WHEN login_throttle.last_failed_at < now() - make_interval(secs => $2) THEN 1
+                    ELSE login_throttle.failed_count + 1
+                END,
+                last_failed_at = now()
+            RETURNING failed_count
+            "#,
+            key,
+            window_seconds
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(failed_count)
+    }
+
+    pub async fn lock(pool: &PgPool, key: &str, locked_until: OffsetDateTime) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE login_throttle
+            SET locked_until = $1
+            WHERE key = $2
+            "#,
+            locked_until,
+            key
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn clear(pool: &PgPool, key: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM login_throttle
+            WHERE key = $1
+            "#,
+            key
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::{
+    common::error::AppError,
+    middleware::client_info::ClientInfo,
+    modules::{
+        audit::{model::AuditEvent, service::AuditService},
+        lockout::{
+            model::{ACCOUNT_POLICY, IP_POLICY, ThrottlePolicy, email_key, ip_key},
+            repository::LockoutRepo,
+        },
+        user::repository::UserRepo,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct LockoutService {
+    pool: PgPool,
+    audit: AuditService,
+}
+
+impl LockoutService {
+    pub fn new(pool: PgPool, audit: AuditService) -> Self {
+        Self { pool, audit }
+    }
+
+    // the same answer for known and unknown emails, so it can't be used to find accounts
+    pub async fn check(&self, email: &str, client: &ClientInfo) -> Result<(), AppError> {
+        let mut keys = vec![email_key(email)];
+        if let Some(ip) = &client.ip {
+            keys.push(ip_key(ip));
+        }
+
+        if LockoutRepo::is_locked(&self.pool, &keys).await? {
+            return Err(AppError::TooManyRequests(
+                "Too many failed sign in attempts, try again later".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn record_failure(&self, email: &str, client: &ClientInfo) -> Result<(), AppError> {
+        let user_id = UserRepo::fetch_by_email(&self.pool, email).await?.map(|user| user.id);
+
+        self.audit
+            .record(user_id, AuditEvent::LoginFailed, client, json!({ "email": email }))
+            .await?;
+
+        if let Some(locked_until) = self.fail(&email_key(email), &ACCOUNT_POLICY).await? {
+            self.audit
+                .record(
+                    user_id,
+                    AuditEvent::AccountLocked,
+                    client,
+                    json!({ "email": email, "locked_until": locked_until.unix_timestamp() }),
+                )
+                .await?;
+        }
+
+        if let Some(ip) = &client.ip
+            && let Some(locked_until) = self.fail(&ip_key(ip), &IP_POLICY).await?
+        {
+            self.audit
+                .record(
+                    None,
+                    AuditEvent::IpLocked,
+                    client,
+                    json!({ "locked_until": locked_until.unix_timestamp() }),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn record_success(&self, email: &str) -> Result<(), AppError> {
+        LockoutRepo::clear(&self.pool, &email_key(email)).await?;
+
+        Ok(())
+    }
+
+    async fn fail(
+        &self,
+        key: &str,
+        policy: &ThrottlePolicy,
+    ) -> Result<Option<time::OffsetDateTime>, AppError> {
+        let failed_count =
+            LockoutRepo::record_failure(&self.pool, key, policy.window.as_seconds_f64()).await?;
+
+        let Some(locked_until) = policy.lock_until(failed_count) else {
+            return Ok(None);
+        };
+
+        LockoutRepo::lock(&self.pool, key, locked_until).await?;
+
+        Ok(Some(locked_until))
+    }
+}
@@ -0,0 +1,107 @@
+use axum::{
+    Extension, Json,
+    extract::State,
+    response::IntoResponse,
+};
+use axum_extra::extract::CookieJar;
+use serde_json::json;
+
+use crate::{
+    common::{error::{AppError, AuthError}, response::ApiResponse},
+    middleware::client_info::ClientInfo,
+    modules::{
+        audit::model::AuditEvent,
+        mfa::model::{ConfirmTotpDto, DisableTotpDto, SecondFactorDto},
+        user::{handler::{restore_on_sign_in, sign_in}, model::UserId},
+    },
+    state::AppState,
+};
+
+pub async fn enroll_totp_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let enrollment = state.mfa_service.enroll(&user_id.0).await?;
+
+    Ok(Json(ApiResponse::success(
+        "Scan the code and confirm it to enable two-factor authentication",
+        enrollment,
+    )))
+}
+
+pub async fn confirm_totp_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    client: ClientInfo,
+    Json(dto): Json<ConfirmTotpDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let codes = state.mfa_service.confirm(&user_id.0, &dto.code).await?;
+
+    state
+        .audit_service
+        .record(Some(user_id.0), AuditEvent::TwoFactorEnabled, &client, json!({}))
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        "Two-factor authentication enabled, store the recovery codes somewhere safe",
+        codes,
+    )))
+}
+
+pub async fn disable_totp_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    client: ClientInfo,
+    Json(dto): Json<DisableTotpDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    state.mfa_service.disable(&user_id.0, &dto.password).await?;
+
+    state
+        .audit_service
+        .record(Some(user_id.0), AuditEvent::TwoFactorDisabled, &client, json!({}))
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        "Two-factor authentication disabled successfuly",
+        None::<()>,
+    )))
+}
+
+pub async fn second_factor_login_handler(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    cookies: CookieJar,
+    Json(dto): Json<SecondFactorDto>,
+) -> Result<impl IntoResponse, AppError> {
+    let challenge_token = dto.challenge_token.trim();
+
+    let user_id = state.mfa_service.challenge_owner(challenge_token).await?;
+    let user = state.user_service.get(user_id).await?;
+
+    // wrong codes count against the same lockout as wrong passwords, fresh challenges don't reset it
+    state.lockout_service.check(&user.email, &client).await?;
+
+    match state.mfa_service.complete_challenge(challenge_token, &dto.code).await {
+        Ok(_) => {}
+        Err(AppError::Unauthorized(AuthError::InvalidSecondFactor)) => {
+            state.lockout_service.record_failure(&user.email, &client).await?;
+            return Err(AppError::Unauthorized(AuthError::InvalidSecondFactor));
+        }
+        Err(e) => return Err(e),
+    }
+
+    state.lockout_service.record_success(&user.email).await?;
+    restore_on_sign_in(&state, &client, &user.id).await?;
+
+    let jar = sign_in(&state, &client, cookies, &user).await?;
+
+    state
+        .audit_service
+        .record(Some(user.id), AuditEvent::LoginSucceeded, &client, json!({ "second_factor": true }))
+        .await?;
+
+    Ok((
+        jar,
+        Json(ApiResponse::success("User login successfuly", user)),
+    ))
+}
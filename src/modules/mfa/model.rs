@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use uuid::Uuid;
+
+use crate::common::error::ValidationError;
+
+#[derive(Debug, FromRow)]
+pub struct TotpState {
+    pub email: String,
+    pub totp_secret: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, FromRow)]
+pub struct LoginChallenge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SecondFactorChallenge {
+    pub second_factor_required: bool,
+    pub challenge_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmTotpDto {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DisableTotpDto {
+    pub password: String,
+}
+
+// `code` is either the current authenticator code or one of the recovery codes
+#[derive(Debug, Deserialize)]
+pub struct SecondFactorDto {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+pub enum SecondFactorCode {
+    Totp(String),
+    Recovery(String),
+}
+
+impl TryFrom<&str> for SecondFactorCode {
+    type Error = ValidationError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let code: String = value
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(SecondFactorCode::Totp(code));
+        }
+
+        if code.len() == 10 && code.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(SecondFactorCode::Recovery(code));
+        }
+
+        Err(ValidationError::InvalidTwoFactorCode)
+    }
+}
@@ -0,0 +1,219 @@
+use sqlx::{PgPool, Result};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::mfa::model::{LoginChallenge, TotpState};
+
+pub struct MfaRepo;
+
+impl MfaRepo {
+    pub async fn fetch_state(pool: &PgPool, user_id: &Uuid) -> Result<Option<TotpState>> {
+        let state = sqlx::query_as!(
+            TotpState,
+            r#"
+            SELECT email, totp_secret, totp_enabled_at IS NOT NULL AS "enabled!"
+            FROM users
+            WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(state)
+    }
+
+    // a pending secret only becomes active once a code for it is confirmed
+    pub async fn set_pending_secret(pool: &PgPool, user_id: &Uuid, secret: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET totp_secret = $1, totp_last_step = NULL
+            WHERE id = $2 AND totp_enabled_at IS NULL
+            "#,
+            secret,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn enable(pool: &PgPool, user_id: &Uuid, code_hashes: &[String]) -> Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET totp_enabled_at = now()
+            WHERE id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM totp_recovery_codes
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO totp_recovery_codes (user_id, code_hash)
+            SELECT $1, unnest($2::text[])
+            "#,
+            user_id,
+            code_hashes
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn disable(pool: &PgPool, user_id: &Uuid) -> Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL
+            WHERE id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM totp_recovery_codes
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // only moves forward, so the same or an older code is rejected
+    pub async fn record_step(pool: &PgPool, user_id: &Uuid, step: i64) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET totp_last_step = $1
+            WHERE id = $2 AND (totp_last_step IS NULL OR totp_last_step < $1)
+            "#,
+            step,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn use_recovery_code(pool: &PgPool, user_id: &Uuid, code_hash: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE totp_recovery_codes
+            SET used_at = now()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            code_hash
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn create_challenge(
+        pool: &PgPool,
+        user_id: &Uuid,
+        token_hash: &str,
+        expires_at: OffsetDateTime,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO login_challenges (user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            "#,
+            user_id,
+            token_hash,
+            expires_at
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn fetch_challenge(
+        pool: &PgPool,
+        token_hash: &str,
+        max_attempts: i32,
+    ) -> Result<Option<LoginChallenge>> {
+        let challenge = sqlx::query_as!(
+            LoginChallenge,
+            r#"
+            SELECT id, user_id
+            FROM login_challenges
+            WHERE token_hash = $1
+                AND used_at IS NULL
+                AND expires_at > now()
+                AND attempts < $2
+            "#,
+            token_hash,
+            max_attempts
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(challenge)
+    }
+
+    pub async fn record_failed_attempt(pool: &PgPool, challenge_id: &Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE login_challenges
+            SET attempts = attempts + 1
+            WHERE id = $1
+            "#,
+            challenge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn consume_challenge(pool: &PgPool, challenge_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE login_challenges
+            SET used_at = now()
+            WHERE id = $1 AND used_at IS NULL
+            "#,
+            challenge_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
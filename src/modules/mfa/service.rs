@@ -0,0 +1,219 @@
+use rand::Rng;
+use sqlx::PgPool;
+use subtle::ConstantTimeEq;
+use time::{Duration, OffsetDateTime};
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, AuthError, NotFoundError, ValidationError},
+    modules::{
+        mfa::{
+            model::{RecoveryCodes, SecondFactorCode, TotpEnrollment},
+            repository::MfaRepo,
+        },
+        user::repository::UserRepo,
+    },
+    utils::{
+        password::{PasswordCheck, PasswordConfig, verify_password},
+        token::{generate_token, hash_token},
+    },
+};
+
+const TOTP_ISSUER: &str = "Wiki";
+const TOTP_STEP_SECONDS: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+const CHALLENGE_TTL_MINUTES: i64 = 5;
+const CHALLENGE_MAX_ATTEMPTS: i32 = 5;
+
+#[derive(Debug, Clone)]
+pub struct MfaService {
+    pool: PgPool,
+    password_config: PasswordConfig,
+}
+
+impl MfaService {
+    pub fn new(pool: PgPool, password_config: PasswordConfig) -> Self {
+        Self {
+            pool,
+            password_config,
+        }
+    }
+
+    pub async fn enroll(&self, user_id: &Uuid) -> Result<TotpEnrollment, AppError> {
+        let state = MfaRepo::fetch_state(&self.pool, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        if state.enabled {
+            return Err(AppError::Validation(ValidationError::TwoFactorAlreadyEnabled));
+        }
+
+        let secret = match Secret::generate_secret().to_encoded() {
+            Secret::Encoded(secret) => secret,
+            Secret::Raw(_) => return Err(AppError::Failed("Failed to encode TOTP secret".into())),
+        };
+
+        let totp = build_totp(&secret, &state.email)?;
+
+        MfaRepo::set_pending_secret(&self.pool, user_id, &secret).await?;
+
+        Ok(TotpEnrollment {
+            secret,
+            otpauth_uri: totp.get_url(),
+        })
+    }
+
+    pub async fn confirm(&self, user_id: &Uuid, code: &str) -> Result<RecoveryCodes, AppError> {
+        let state = MfaRepo::fetch_state(&self.pool, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        if state.enabled {
+            return Err(AppError::Validation(ValidationError::TwoFactorAlreadyEnabled));
+        }
+
+        let secret = state
+            .totp_secret
+            .ok_or(AppError::Validation(ValidationError::TwoFactorNotEnrolled))?;
+
+        let totp = build_totp(&secret, &state.email)?;
+
+        let step = matching_step(&totp, code.trim())
+            .ok_or(AppError::Validation(ValidationError::InvalidTwoFactorCode))?;
+
+        MfaRepo::record_step(&self.pool, user_id, step).await?;
+
+        let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| generate_recovery_code()).collect();
+        let code_hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| hash_token(&code.replace('-', "")))
+            .collect();
+
+        MfaRepo::enable(&self.pool, user_id, &code_hashes).await?;
+
+        Ok(RecoveryCodes { recovery_codes })
+    }
+
+    pub async fn disable(&self, user_id: &Uuid, password: &str) -> Result<(), AppError> {
+        let user = UserRepo::fetch_by_id_with_password(&self.pool, *user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        if verify_password(password, &user.password, &self.password_config).await? == PasswordCheck::Invalid {
+            return Err(AppError::Validation(ValidationError::InvalidPassword));
+        }
+
+        MfaRepo::disable(&self.pool, user_id).await?;
+
+        Ok(())
+    }
+
+    // returns a challenge token when the account has a second factor, None otherwise
+    pub async fn start_challenge(&self, user_id: &Uuid) -> Result<Option<String>, AppError> {
+        let state = MfaRepo::fetch_state(&self.pool, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        if !state.enabled {
+            return Ok(None);
+        }
+
+        let token = generate_token();
+        let expires_at = OffsetDateTime::now_utc() + Duration::minutes(CHALLENGE_TTL_MINUTES);
+
+        MfaRepo::create_challenge(&self.pool, user_id, &hash_token(&token), expires_at).await?;
+
+        Ok(Some(token))
+    }
+
+    // who the challenge belongs to, so the caller can apply the account lockout before checking the code
+    pub async fn challenge_owner(&self, challenge_token: &str) -> Result<Uuid, AppError> {
+        let challenge = MfaRepo::fetch_challenge(&self.pool, &hash_token(challenge_token), CHALLENGE_MAX_ATTEMPTS)
+            .await?
+            .ok_or(AppError::Unauthorized(AuthError::InvalidLoginChallenge))?;
+
+        Ok(challenge.user_id)
+    }
+
+    pub async fn complete_challenge(&self, challenge_token: &str, code: &str) -> Result<Uuid, AppError> {
+        let challenge = MfaRepo::fetch_challenge(&self.pool, &hash_token(challenge_token), CHALLENGE_MAX_ATTEMPTS)
+            .await?
+            .ok_or(AppError::Unauthorized(AuthError::InvalidLoginChallenge))?;
+
+        let code: SecondFactorCode = code.try_into()?;
+
+        if !self.check_code(&challenge.user_id, code).await? {
+            MfaRepo::record_failed_attempt(&self.pool, &challenge.id).await?;
+            return Err(AppError::Unauthorized(AuthError::InvalidSecondFactor));
+        }
+
+        if !MfaRepo::consume_challenge(&self.pool, &challenge.id).await? {
+            return Err(AppError::Unauthorized(AuthError::InvalidLoginChallenge));
+        }
+
+        Ok(challenge.user_id)
+    }
+
+    async fn check_code(&self, user_id: &Uuid, code: SecondFactorCode) -> Result<bool, AppError> {
+        match code {
+            SecondFactorCode::Totp(code) => {
+                let state = MfaRepo::fetch_state(&self.pool, user_id)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+                let Some(secret) = state.totp_secret.filter(|_| state.enabled) else {
+                    return Ok(false);
+                };
+
+                let Some(step) = matching_step(&build_totp(&secret, &state.email)?, &code) else {
+                    return Ok(false);
+                };
+
+                Ok(MfaRepo::record_step(&self.pool, user_id, step).await?)
+            }
+            SecondFactorCode::Recovery(code) => {
+                Ok(MfaRepo::use_recovery_code(&self.pool, user_id, &hash_token(&code)).await?)
+            }
+        }
+    }
+}
+
+fn build_totp(secret: &str, email: &str) -> Result<TOTP, AppError> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|_| AppError::Failed("Stored TOTP secret is malformed".into()))?;
+
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        TOTP_STEP_SECONDS,
+        bytes,
+        Some(TOTP_ISSUER.to_string()),
+        email.to_string(),
+    )
+    .map_err(|e| AppError::Failed(format!("Failed to build TOTP: {e}")))
+}
+
+// accepts the previous, current and next step to allow for clock drift
+fn matching_step(totp: &TOTP, code: &str) -> Option<i64> {
+    let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+    let current = now / TOTP_STEP_SECONDS;
+
+    [current - 1, current, current + 1]
+        .into_iter()
+        .find(|step| {
+            let expected = totp.generate(step * TOTP_STEP_SECONDS);
+            bool::from(expected.as_bytes().ct_eq(code.as_bytes()))
+        })
+        .map(|step| step as i64)
+}
+
+// 10 hex chars shown as xxxxx-xxxxx, stored without the dash
+fn generate_recovery_code() -> String {
+    let bytes: [u8; 5] = rand::rng().random();
+    let code = hex::encode(bytes);
+
+    format!("{}-{}", &code[..5], &code[5..])
+}
@@ -0,0 +1,62 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    modules::{
+        mission::model::{CreateMissionDto, NewMission},
+        user::model::UserId,
+    },
+    state::AppState,
+};
+
+pub async fn create_mission_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Json(dto): Json<CreateMissionDto>,
+) -> Result<(StatusCode, Json<ApiResponse<impl serde::Serialize>>), AppError> {
+    let new_mission: NewMission = dto.try_into()?;
+
+    let mission = state.mission_service.create(&user_id.0, new_mission).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success("Mission created successfuly", mission)),
+    ))
+}
+
+pub async fn fetch_all_missions_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let missions = state.mission_service.fetch_all(&user_id.0).await?;
+
+    Ok(Json(ApiResponse::success("All missions fetch successfuly", missions)))
+}
+
+pub async fn get_mission_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(mission_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let mission = state.mission_service.get(&user_id.0, &mission_id).await?;
+
+    Ok(Json(ApiResponse::success("Mission fetch successfuly", mission)))
+}
+
+pub async fn delete_mission_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(mission_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    state.mission_service.delete(&user_id.0, &mission_id).await?;
+
+    Ok(Json(ApiResponse::success(
+        "Mission deleted successfuly",
+        None::<()>,
+    )))
+}
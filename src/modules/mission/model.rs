@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use time::{Date, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    common::error::ValidationError,
+    modules::todo::model::{CreateCategoryDto, Priority, due_date, valid_estimate},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Weekday {
+    Mo,
+    Tu,
+    We,
+    Th,
+    Fr,
+    Sa,
+    Su,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Mo,
+        Weekday::Tu,
+        Weekday::We,
+        Weekday::Th,
+        Weekday::Fr,
+        Weekday::Sa,
+        Weekday::Su,
+    ];
+
+    // ISO numbering, monday is 1
+    pub fn iso(self) -> i16 {
+        self as i16 + 1
+    }
+
+    pub fn from_iso(day: i16) -> Option<Self> {
+        Self::ALL.get(usize::try_from(day - 1).ok()?).copied()
+    }
+}
+
+// modelled after RRULE, e.g. {"frequency": "weekly", "by_day": ["MO", "WE", "FR"]}
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "frequency", rename_all = "lowercase")]
+pub enum Schedule {
+    Daily,
+    Weekdays,
+    Weekly { by_day: Vec<Weekday> },
+    Interval { every_days: i32 },
+}
+
+impl Schedule {
+    pub fn frequency(&self) -> &'static str {
+        match self {
+            Schedule::Daily => "daily",
+            Schedule::Weekdays => "weekdays",
+            Schedule::Weekly { .. } => "weekly",
+            Schedule::Interval { .. } => "interval",
+        }
+    }
+
+    pub fn by_day(&self) -> Vec<i16> {
+        match self {
+            Schedule::Weekly { by_day } => by_day.iter().map(|day| day.iso()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn every_days(&self) -> Option<i32> {
+        match self {
+            Schedule::Interval { every_days } => Some(*every_days),
+            _ => None,
+        }
+    }
+
+    // the columns are kept consistent by the table's checks
+    fn from_columns(frequency: &str, by_day: Vec<i16>, every_days: Option<i32>) -> Self {
+        match (frequency, every_days) {
+            ("weekdays", _) => Schedule::Weekdays,
+            ("weekly", _) => Schedule::Weekly {
+                by_day: by_day.into_iter().filter_map(Weekday::from_iso).collect(),
+            },
+            ("interval", Some(every_days)) => Schedule::Interval { every_days },
+            _ => Schedule::Daily,
+        }
+    }
+
+    fn normalized(self) -> Result<Self, ValidationError> {
+        match self {
+            Schedule::Weekly { mut by_day } => {
+                by_day.sort();
+                by_day.dedup();
+
+                if by_day.is_empty() {
+                    return Err(ValidationError::InvalidSchedule);
+                }
+
+                Ok(Schedule::Weekly { by_day })
+            }
+            Schedule::Interval { every_days } if !(1..=365).contains(&every_days) => {
+                Err(ValidationError::InvalidSchedule)
+            }
+            schedule => Ok(schedule),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateMissionDto {
+    pub todo: String,
+    pub description: String,
+    pub category_slug: String,
+    #[serde(default)]
+    pub priority: Priority,
+    pub estimated_minutes: Option<i32>,
+    pub schedule: Schedule,
+    // interval missions count their steps from here, today when left out
+    #[serde(default, with = "due_date::option")]
+    pub starts_on: Option<Date>,
+    #[serde(default, with = "due_date::option")]
+    pub ends_on: Option<Date>,
+}
+
+pub struct NewMission {
+    pub title: String,
+    pub description: String,
+    pub category_slug: String,
+    pub priority: Priority,
+    pub estimated_minutes: Option<i32>,
+    pub schedule: Schedule,
+    pub starts_on: Date,
+    pub ends_on: Option<Date>,
+}
+
+impl TryFrom<CreateMissionDto> for NewMission {
+    type Error = ValidationError;
+
+    fn try_from(value: CreateMissionDto) -> Result<Self, Self::Error> {
+        let title = value.todo.trim();
+        let description = value.description.trim();
+
+        if title.len() < 5 {
+            return Err(ValidationError::TodoTooShort);
+        }
+
+        if description.len() < 5 {
+            return Err(ValidationError::DescriptionTooShort);
+        }
+
+        if value.estimated_minutes.is_some_and(|minutes| !valid_estimate(minutes)) {
+            return Err(ValidationError::InvalidEstimate);
+        }
+
+        // resolved here so the check and the stored row use the same today
+        let starts_on = value.starts_on.unwrap_or_else(|| OffsetDateTime::now_utc().date());
+
+        if value.ends_on.is_some_and(|ends_on| ends_on < starts_on) {
+            return Err(ValidationError::InvalidSchedule);
+        }
+
+        Ok(Self {
+            title: title.to_string(),
+            description: description.to_string(),
+            category_slug: value.category_slug,
+            priority: value.priority,
+            estimated_minutes: value.estimated_minutes,
+            schedule: value.schedule.normalized()?,
+            starts_on,
+            ends_on: value.ends_on,
+        })
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct MissionRow {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub category_name: String,
+    pub category_slug: String,
+    pub priority: Priority,
+    pub estimated_minutes: Option<i32>,
+    pub frequency: String,
+    pub by_day: Vec<i16>,
+    pub every_days: Option<i32>,
+    pub starts_on: Date,
+    pub ends_on: Option<Date>,
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MissionResponse {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub category: CreateCategoryDto,
+    pub priority: Priority,
+    pub estimated_minutes: Option<i32>,
+    pub schedule: Schedule,
+    #[serde(with = "due_date")]
+    pub starts_on: Date,
+    #[serde(with = "due_date::option")]
+    pub ends_on: Option<Date>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl From<MissionRow> for MissionResponse {
+    fn from(row: MissionRow) -> Self {
+        Self {
+            id: row.id,
+            title: row.title,
+            description: row.description,
+            category: CreateCategoryDto {
+                name: row.category_name,
+                slug: row.category_slug,
+            },
+            priority: row.priority,
+            estimated_minutes: row.estimated_minutes,
+            schedule: Schedule::from_columns(&row.frequency, row.by_day, row.every_days),
+            starts_on: row.starts_on,
+            ends_on: row.ends_on,
+            created_at: row.created_at,
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use sqlx::{PgPool, Postgres, Result, Transaction};
+use time::Date;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, NotFoundError},
+    modules::{
+        mission::model::{MissionRow, NewMission},
+        todo::model::Priority,
+    },
+};
+
+pub struct MissionRepo;
+
+impl MissionRepo {
+    pub async fn insert(pool: &PgPool, user_id: &Uuid, new: &NewMission) -> Result<Uuid, AppError> {
+        let category_id = sqlx::query_scalar!(
+            "SELECT id FROM categories WHERE slug = $1 AND user_id = $2",
+            new.category_slug,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::NotFound(NotFoundError::CategoryNotFound))?;
+
+        let mission_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO missions (user_id, category_id, title, description, priority, estimated_minutes,
+                frequency, by_day, every_days, starts_on, ends_on)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING id
+            "#,
+            user_id,
+            category_id,
+            new.title,
+            new.description,
+            new.priority as Priority,
+            new.estimated_minutes,
+            new.schedule.frequency(),
+            &new.schedule.by_day(),
+            new.schedule.every_days(),
+            new.starts_on,
+            new.ends_on
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(mission_id)
+    }
+
+    pub async fn fetch(pool: &PgPool, user_id: &Uuid, mission_id: &Uuid) -> Result<Option<MissionRow>> {
+        let mission = sqlx::query_as!(
+            MissionRow,
+            r#"
+            SELECT m.id, m.title, m.description, c.name AS category_name, c.slug AS category_slug,
+                m.priority AS "priority: Priority", m.estimated_minutes, m.frequency, m.by_day,
+                m.every_days, m.starts_on, m.ends_on, m.created_at
+            FROM missions m
+            JOIN categories c ON c.id = m.category_id
+            WHERE m.id = $1 AND m.user_id = $2
+            "#,
+            mission_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(mission)
+    }
+
+    pub async fn fetch_all(pool: &PgPool, user_id: &Uuid) -> Result<Vec<MissionRow>> {
+        let missions = sqlx::query_as!(
+            MissionRow,
+            r#"
+            SELECT m.id, m.title, m.description, c.name AS category_name, c.slug AS category_slug,
+                m.priority AS "priority: Priority", m.estimated_minutes, m.frequency, m.by_day,
+                m.every_days, m.starts_on, m.ends_on, m.created_at
+            FROM missions m
+            JOIN categories c ON c.id = m.category_id
+            WHERE m.user_id = $1
+            ORDER BY m.priority, m.created_at
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(missions)
+    }
+
+    // todos already created from the mission stay where they are
+    pub async fn delete(pool: &PgPool, user_id: &Uuid, mission_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM missions WHERE id = $1 AND user_id = $2",
+            mission_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // creates `day` for everyone with a mission due that day, days that already exist are left alone
+    pub async fn open_days(tx: &mut Transaction<'_, Postgres>, day: Date) -> Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO daily_progress (user_id, day)
+            SELECT DISTINCT m.user_id, $1::date
+            FROM missions m
+            JOIN users u ON u.id = m.user_id
+            WHERE mission_occurs_on(m, $1) AND u.deleted_at IS NULL
+            ON CONFLICT (user_id, day) DO NOTHING
+            "#,
+            day
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // copies every mission due on `day` into its owner's daily progress as a new todo, limited to
+    // one user when `user_id` is set. a mission is only ever copied once per day
+    pub async fn populate(
+        tx: &mut Transaction<'_, Postgres>,
+        day: Date,
+        user_id: Option<&Uuid>,
+    ) -> Result<i64> {
+        let created = sqlx::query_scalar!(
+            r#"
+            WITH due AS (
+                SELECT gen_random_uuid() AS todo_id, m.id AS mission_id, m.user_id, m.category_id,
+                    m.title, m.description, m.priority, m.estimated_minutes, dp.id AS daily_progress_id
+                FROM missions m
+                JOIN daily_progress dp ON dp.user_id = m.user_id AND dp.day = $1
+                WHERE mission_occurs_on(m, $1)
+                AND ($2::uuid IS NULL OR m.user_id = $2)
+                AND NOT EXISTS (
+                    SELECT 1 FROM mission_occurrences o
+                    WHERE o.mission_id = m.id AND o.day = $1
+                )
+            ),
+            new_todos AS (
+                INSERT INTO todos (id, user_id, category_id, title, description, priority, estimated_minutes)
+                SELECT todo_id, user_id, category_id, title, description, priority, estimated_minutes
+                FROM due
+            ),
+            new_progress_todos AS (
+                INSERT INTO daily_progress_todos (todo_id, daily_progress_id)
+                SELECT todo_id, daily_progress_id FROM due
+            ),
+            new_occurrences AS (
+                INSERT INTO mission_occurrences (mission_id, day, todo_id)
+                SELECT mission_id, $1, todo_id FROM due
+            )
+            SELECT COUNT(*) AS "count!" FROM due
+            "#,
+            day,
+            user_id
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(created)
+    }
+}
@@ -0,0 +1,62 @@
+use sqlx::PgPool;
+use time::Date;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, NotFoundError},
+    modules::mission::{
+        model::{MissionResponse, NewMission},
+        repository::MissionRepo,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct MissionService {
+    pool: PgPool,
+}
+
+impl MissionService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, user_id: &Uuid, new: NewMission) -> Result<MissionResponse, AppError> {
+        let mission_id = MissionRepo::insert(&self.pool, user_id, &new).await?;
+
+        self.get(user_id, &mission_id).await
+    }
+
+    pub async fn get(&self, user_id: &Uuid, mission_id: &Uuid) -> Result<MissionResponse, AppError> {
+        let mission = MissionRepo::fetch(&self.pool, user_id, mission_id)
+            .await?
+            .ok_or(AppError::NotFound(NotFoundError::MissionNotFound))?;
+
+        Ok(mission.into())
+    }
+
+    pub async fn fetch_all(&self, user_id: &Uuid) -> Result<Vec<MissionResponse>, AppError> {
+        let missions = MissionRepo::fetch_all(&self.pool, user_id).await?;
+
+        Ok(missions.into_iter().map(MissionResponse::from).collect())
+    }
+
+    pub async fn delete(&self, user_id: &Uuid, mission_id: &Uuid) -> Result<(), AppError> {
+        if !MissionRepo::delete(&self.pool, user_id, mission_id).await? {
+            return Err(AppError::NotFound(NotFoundError::MissionNotFound));
+        }
+
+        Ok(())
+    }
+
+    // opens `day` for everyone with a mission due and fills in the missions, returns how many todos were created
+    pub async fn populate(&self, day: Date) -> Result<i64, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        MissionRepo::open_days(&mut tx, day).await?;
+        let created = MissionRepo::populate(&mut tx, day, None).await?;
+
+        tx.commit().await?;
+
+        Ok(created)
+    }
+}
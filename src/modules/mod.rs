@@ -1,4 +1,16 @@
 pub mod todo;
 pub mod user;
 pub mod progress;
-pub mod rooms;
\ No newline at end of file
+pub mod rooms;
+pub mod session;
+pub mod token;
+pub mod verification;
+pub mod mfa;
+pub mod audit;
+pub mod lockout;
+pub mod admin;
+pub mod export;
+pub mod follow;
+pub mod block;
+pub mod privacy;
+pub mod mission;
@@ -0,0 +1,48 @@
+use axum::{Extension, Json, extract::State};
+use serde_json::json;
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    middleware::client_info::ClientInfo,
+    modules::{
+        audit::model::AuditEvent,
+        privacy::model::{UpdatePrivacyDto, Visibility},
+        user::model::UserId,
+    },
+    state::AppState,
+};
+
+pub async fn get_privacy_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let settings = state.privacy_service.settings(&user_id.0).await?;
+
+    Ok(Json(ApiResponse::success("fetch privacy settings successfuly", settings)))
+}
+
+pub async fn update_privacy_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    client: ClientInfo,
+    Json(dto): Json<UpdatePrivacyDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let settings = state.privacy_service.update(&user_id.0, dto).await?;
+
+    // once the profile is public nobody needs approval to follow
+    if settings.profile == Visibility::Public {
+        state.follow_service.accept_all(&user_id.0).await?;
+    }
+
+    state
+        .audit_service
+        .record(
+            Some(user_id.0),
+            AuditEvent::VisibilityChanged,
+            &client,
+            json!(settings),
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success("privacy settings updated successfuly", settings)))
+}
@@ -0,0 +1,5 @@
+pub mod handler;
+pub mod model;
+pub mod policy;
+pub mod repository;
+pub mod service;
\ No newline at end of file
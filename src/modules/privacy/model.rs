@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum Visibility {
+    Public,
+    Followers,
+    Private,
+}
+
+impl Visibility {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Followers => "followers",
+            Visibility::Private => "private",
+        }
+    }
+}
+
+// the parts of an account that can be shown to other users
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Profile,
+    DailyProgress,
+    Stats,
+    RoomMemberships,
+    Followers,
+}
+
+#[derive(Debug, Clone, Copy, FromRow, Serialize)]
+pub struct PrivacySettings {
+    pub profile: Visibility,
+    pub daily_progress: Visibility,
+    pub stats: Visibility,
+    pub room_memberships: Visibility,
+    pub followers: Visibility,
+}
+
+impl Default for PrivacySettings {
+    // new accounts are only visible to the followers they approve
+    fn default() -> Self {
+        Self {
+            profile: Visibility::Followers,
+            daily_progress: Visibility::Followers,
+            stats: Visibility::Followers,
+            room_memberships: Visibility::Followers,
+            followers: Visibility::Followers,
+        }
+    }
+}
+
+impl PrivacySettings {
+    pub fn get(&self, section: Section) -> Visibility {
+        match section {
+            Section::Profile => self.profile,
+            Section::DailyProgress => self.daily_progress,
+            Section::Stats => self.stats,
+            Section::RoomMemberships => self.room_memberships,
+            Section::Followers => self.followers,
+        }
+    }
+
+    pub fn apply(self, dto: UpdatePrivacyDto) -> Self {
+        Self {
+            profile: dto.profile.unwrap_or(self.profile),
+            daily_progress: dto.daily_progress.unwrap_or(self.daily_progress),
+            stats: dto.stats.unwrap_or(self.stats),
+            room_memberships: dto.room_memberships.unwrap_or(self.room_memberships),
+            followers: dto.followers.unwrap_or(self.followers),
+        }
+    }
+}
+
+// sections left out keep their current visibility
+#[derive(Debug, Deserialize)]
+pub struct UpdatePrivacyDto {
+    pub profile: Option<Visibility>,
+    pub daily_progress: Option<Visibility>,
+    pub stats: Option<Visibility>,
+    pub room_memberships: Option<Visibility>,
+    pub followers: Option<Visibility>,
+}
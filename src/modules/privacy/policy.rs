@@ -0,0 +1,46 @@
+// the single place that decides who can read which part of an account
+use crate::{
+    common::error::{AppError, ValidationError},
+    modules::privacy::model::{PrivacySettings, Section, Visibility},
+};
+
+// how the viewer relates to the owner of the account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Owner,
+    Follower,
+    // anonymous, or signed in without an accepted follow
+    Stranger,
+}
+
+impl Visibility {
+    pub fn allows(self, relation: Relation) -> bool {
+        matches!(
+            (self, relation),
+            (_, Relation::Owner)
+                | (Visibility::Public, _)
+                | (Visibility::Followers, Relation::Follower)
+        )
+    }
+}
+
+// a viewer's access to one account, built once per request by PrivacyService::audience
+#[derive(Debug, Clone, Copy)]
+pub struct Audience {
+    pub relation: Relation,
+    pub settings: PrivacySettings,
+}
+
+impl Audience {
+    pub fn can_view(&self, section: Section) -> bool {
+        self.settings.get(section).allows(self.relation)
+    }
+
+    pub fn ensure(&self, section: Section) -> Result<(), AppError> {
+        if !self.can_view(section) {
+            return Err(AppError::Validation(ValidationError::UnauthorizedAccess));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+use crate::modules::privacy::model::{PrivacySettings, Visibility};
+
+pub struct PrivacyRepo;
+
+impl PrivacyRepo {
+    pub async fn fetch(pool: &PgPool, user_id: &Uuid) -> Result<Option<PrivacySettings>> {
+        let settings = sqlx::query_as!(
+            PrivacySettings,
+            r#"
+            SELECT
+                profile AS "profile: Visibility",
+                daily_progress AS "daily_progress: Visibility",
+                stats AS "stats: Visibility",
+                room_memberships AS "room_memberships: Visibility",
+                followers AS "followers: Visibility"
+            FROM privacy_settings
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(settings)
+    }
+
+    pub async fn save(pool: &PgPool, user_id: &Uuid, settings: &PrivacySettings) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO privacy_settings (user_id, profile, daily_progress, stats, room_memberships, followers)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id) DO UPDATE SET
+                profile = EXCLUDED.profile,
+                daily_progress = EXCLUDED.daily_progress,
+                stats = EXCLUDED.stats,
+                room_memberships = EXCLUDED.room_memberships,
+                followers = EXCLUDED.followers,
+                updated_at = now()
+            "#,
+            user_id,
+            settings.profile as Visibility,
+            settings.daily_progress as Visibility,
+            settings.stats as Visibility,
+            settings.room_memberships as Visibility,
+            settings.followers as Visibility
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, NotFoundError},
+    modules::{
+        block::repository::BlockRepo,
+        follow::{model::FollowStatus, repository::FollowRepo},
+        privacy::{
+            model::{PrivacySettings, UpdatePrivacyDto},
+            policy::{Audience, Relation},
+            repository::PrivacyRepo,
+        },
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct PrivacyService {
+    pool: PgPool,
+}
+
+impl PrivacyService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn settings(&self, user_id: &Uuid) -> Result<PrivacySettings, AppError> {
+        let settings = PrivacyRepo::fetch(&self.pool, user_id).await?.unwrap_or_default();
+
+        Ok(settings)
+    }
+
+    pub async fn update(&self, user_id: &Uuid, dto: UpdatePrivacyDto) -> Result<PrivacySettings, AppError> {
+        let settings = self.settings(user_id).await?.apply(dto);
+
+        PrivacyRepo::save(&self.pool, user_id, &settings).await?;
+
+        Ok(settings)
+    }
+
+    // someone the owner blocked is told the account doesn't exist
+    pub async fn audience(&self, viewer_id: Option<Uuid>, owner_id: &Uuid) -> Result<Audience, AppError> {
+        let settings = self.settings(owner_id).await?;
+
+        let relation = match viewer_id {
+            None => Relation::Stranger,
+            Some(viewer_id) if viewer_id == *owner_id => Relation::Owner,
+            Some(viewer_id) => {
+                if BlockRepo::is_blocked(&self.pool, owner_id, &viewer_id).await? {
+                    return Err(AppError::NotFound(NotFoundError::UserNotFound));
+                }
+
+                match FollowRepo::fetch_status(&self.pool, &viewer_id, owner_id).await? {
+                    Some(FollowStatus::Accepted) => Relation::Follower,
+                    _ => Relation::Stranger,
+                }
+            }
+        };
+
+        Ok(Audience { relation, settings })
+    }
+}
@@ -1,11 +1,11 @@
-use axum::{Extension, Json, extract::{Path, State}};
+use axum::{Extension, Json, extract::{Path, Query, State}};
 use axum_macros::debug_handler;
 use uuid::Uuid;
 use time::{Date, format_description::well_known::Iso8601};
 
 use crate::{
     common::{error::AppError, response::ApiResponse},
-    modules::{progress::{model::{DailyProgressDto, DailyProgressTodoResponse, IsExitsResponse}, service::ProgressService}, user::model::UserId},
+    modules::{follow::model::PageDto, privacy::model::Section, progress::{model::{DailyProgressDto, DailyProgressTodoResponse, IsExitsResponse}, service::ProgressService}, user::model::UserId},
     state::AppState,
 };
 
@@ -45,9 +45,10 @@ pub async fn create_daily_progress_todo_handler(
 
 pub async fn fetch_daily_progress_todo_by_id(
     State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
     Path(progress_todo_id): Path<Uuid>
 ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
-    let daily_progress_todo = ProgressService::fetch_daily_progress_todo_id(&state.progress_service, &progress_todo_id).await?;
+    let daily_progress_todo = ProgressService::fetch_daily_progress_todo_id(&state.progress_service, &progress_todo_id, &user_id.0).await?;
 
     Ok(Json(ApiResponse::success("Todo updated successfuly", daily_progress_todo)))
 
@@ -64,9 +65,10 @@ pub async fn toggle_daily_progress_todo_handler(
 }
 pub async fn fetch_all_daily_progress_todos(
     State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
     Path(daily_progress_id): Path<Uuid>
 )-> Result<Json<ApiResponse<impl serde::Serialize>>, AppError>  {
-    let todos = ProgressService::fetch_all_daily_progress_todo(&state.progress_service, &daily_progress_id).await?;
+    let todos = ProgressService::fetch_all_daily_progress_todo(&state.progress_service, &daily_progress_id, &user_id.0).await?;
 
     Ok(Json(ApiResponse::success("fetched all successfuly", todos)))
 }
@@ -89,9 +91,47 @@ pub async fn is_progress_exits_handler(
 
 pub async fn delete_daily_progress_todo_handler(
     State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
     Path(progress_todo_id): Path<Uuid>
 ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
-    ProgressService::delete_daily_progress_todo(&state.progress_service, &progress_todo_id).await?;
+    ProgressService::delete_daily_progress_todo(&state.progress_service, &progress_todo_id, &user_id.0).await?;
 
     Ok(Json(ApiResponse::success("Successfully deleted daily progress todo", None::<()>)))
-}
\ No newline at end of file
+}
+
+pub async fn user_progress_handler(
+    State(state): State<AppState>,
+    user_id: Option<Extension<UserId>>,
+    Path(username): Path<String>,
+    Query(page): Query<PageDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let user = state.user_service.get_user_by_username(&username).await?;
+
+    state
+        .privacy_service
+        .audience(user_id.map(|Extension(user_id)| user_id.0), &user.id)
+        .await?
+        .ensure(Section::DailyProgress)?;
+
+    let progress = state.progress_service.fetch_summaries(&user.id, page.into()).await?;
+
+    Ok(Json(ApiResponse::success("fetch progress successfuly", progress)))
+}
+
+pub async fn user_stats_handler(
+    State(state): State<AppState>,
+    user_id: Option<Extension<UserId>>,
+    Path(username): Path<String>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let user = state.user_service.get_user_by_username(&username).await?;
+
+    state
+        .privacy_service
+        .audience(user_id.map(|Extension(user_id)| user_id.0), &user.id)
+        .await?
+        .ensure(Section::Stats)?;
+
+    let stats = state.progress_service.fetch_stats(&user.id).await?;
+
+    Ok(Json(ApiResponse::success("fetch stats successfuly", stats)))
+}
@@ -3,6 +3,8 @@ use sqlx::prelude::FromRow;
 use uuid::Uuid;
 use time::{Date, PrimitiveDateTime};
 
+use crate::modules::todo::model::Priority;
+
 
 #[derive(Debug, FromRow, Serialize)]
 pub struct DailyProgress {
@@ -29,6 +31,8 @@ pub struct DailyProgressTodoDto {
     pub title: String,
     pub description: String,
     pub category_id: Uuid,
+    pub priority: Priority,
+    pub estimated_minutes: Option<i32>,
     pub is_done: bool,
     pub created_at: PrimitiveDateTime
 }
@@ -38,6 +42,9 @@ pub struct DailyProgressTodoResponse {
     pub todo: String,
     pub description: String,
     pub category_slug: String,
+    #[serde(default)]
+    pub priority: Priority,
+    pub estimated_minutes: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -60,7 +67,9 @@ pub struct CompleteDailyProgressTodo {
     pub is_done: bool,
     pub created_at: PrimitiveDateTime,
     pub category_slug: String,
-    pub category_name: String
+    pub category_name: String,
+    pub priority: Priority,
+    pub estimated_minutes: Option<i32>,
 }
 // pub struct
 
@@ -78,4 +87,21 @@ pub struct DailyProgressDto {
 pub struct IsExitsResponse {
     pub id: Option<Uuid>,
     pub is_exits: bool
-}
\ No newline at end of file
+}
+
+// one tracked day as other users see it, without the todos themselves
+#[derive(Debug, FromRow, Serialize)]
+pub struct DailyProgressSummary {
+    pub day: Date,
+    pub todos_total: i64,
+    pub todos_done: i64,
+}
+
+// a day counts towards a streak once at least one of its todos is done
+#[derive(Debug, FromRow, Serialize)]
+pub struct ProgressStats {
+    pub days_tracked: i64,
+    pub todos_done: i64,
+    pub current_streak: i64,
+    pub longest_streak: i64,
+}
@@ -1,23 +1,25 @@
-use sqlx::{PgPool, Result};
+use sqlx::{PgPool, Postgres, Result, Transaction};
 use time::Date;
 use uuid::Uuid;
 
 use crate::{
     common::error::{AppError, NotFoundError},
     modules::{
+        follow::model::Page,
         progress::model::{
-            CompleteDailyProgressTodo, DailyProgress, DailyProgressTodo, DailyProgressTodoDto,
-            DailyProgressTodoResponse, ProgressTodoRespons,
+            CompleteDailyProgressTodo, DailyProgress, DailyProgressSummary, DailyProgressTodo,
+            DailyProgressTodoDto, DailyProgressTodoResponse, ProgressStats, ProgressTodoRespons,
         },
-        todo::model::Todo,
+        todo::model::{Priority, Todo},
     },
 };
 
 pub struct ProgressRepo;
 
 impl ProgressRepo {
+    // the missions job may have opened the day already, then that one is returned
     pub async fn create_daily_progress(
-        pool: &PgPool,
+        tx: &mut Transaction<'_, Postgres>,
         user_id: &Uuid,
         day: Date,
     ) -> Result<DailyProgress> {
@@ -26,12 +28,13 @@ impl ProgressRepo {
             r#"
             INSERT INTO daily_progress (user_id, day)
             VALUES ($1, $2)
+            ON CONFLICT (user_id, day) DO UPDATE SET day = EXCLUDED.day
             RETURNING id, user_id, day, created_at, updated_at
             "#,
             user_id,
             day
         )
-        .fetch_one(pool)
+        .fetch_one(&mut **tx)
         .await?;
 
         Ok(progress)
@@ -82,44 +85,48 @@ impl ProgressRepo {
     ) -> Result<DailyProgressTodoDto, AppError> {
         let mut tx = pool.begin().await?;
 
+        let exits = sqlx::query_scalar!(
+            r#"
+            SELECT 1
+            FROM daily_progress
+            WHERE id = $1 AND user_id = $2
+            "#,
+            daily_progress_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if exits.is_none() {
+            return Err(AppError::NotFound(NotFoundError::DailyProgressNotFound));
+        }
+
         let todos = sqlx::query_as!(
             Todo,
             r#"
-            INSERT INTO todos (user_id, title, description, category_id)
+            INSERT INTO todos (user_id, title, description, category_id, priority, estimated_minutes)
             VALUES ($1, $2, $3, 
         (
             SELECT id
             FROM categories
             WHERE slug = $4 AND user_id =$1
             LIMIT 1
-        )
+        ),
+            $5, $6
             )
-            RETURNING id, user_id, title, description, created_at, updated_at, category_id
+            RETURNING id, user_id, title, description, created_at, updated_at, category_id,
+                priority AS "priority: Priority", due_on, due_time, estimated_minutes
             "#,
             user_id,
             new_todo.todo,
             new_todo.description,
-            new_todo.category_slug
-        )
-        .fetch_one(&mut *tx)
-        .await?;
-
-        let exits = sqlx::query_scalar!(
-            r#"
-            SELECT 1
-            FROM daily_progress
-            WHERE id = $1 AND user_id = $2
-            "#,
-            daily_progress_id,
-            user_id
+            new_todo.category_slug,
+            new_todo.priority as Priority,
+            new_todo.estimated_minutes
         )
         .fetch_one(&mut *tx)
         .await?;
 
-        if exits.is_none() {
-            return Err(AppError::NotFound(NotFoundError::DailyProgressNotFound));
-        }
-
         let daily_progress_todo = sqlx::query_as!(
             DailyProgressTodo,
             r#"
@@ -140,6 +147,8 @@ impl ProgressRepo {
             title: todos.title,
             description: todos.description,
             category_id: todos.category_id,
+            priority: todos.priority,
+            estimated_minutes: todos.estimated_minutes,
             is_done: daily_progress_todo.is_done,
             created_at: daily_progress_todo.created_at,
         };
@@ -150,18 +159,21 @@ impl ProgressRepo {
     pub async fn fetch_daily_progress_todo_by_id(
         pool: &PgPool,
         id: &Uuid,
-    ) -> Result<ProgressTodoRespons> {
+        user_id: &Uuid,
+    ) -> Result<Option<ProgressTodoRespons>> {
         let todo = sqlx::query_as!(
             ProgressTodoRespons,
             r#"
             SELECT pt.id AS progress_todo_id, pt.todo_id, pt.daily_progress_id, pt.is_done, pt.created_at, t.title, t.description
             FROM daily_progress_todos pt
             JOIN todos t ON pt.todo_id = t.id
-            WHERE pt.id = $1
+            JOIN daily_progress dp ON dp.id = pt.daily_progress_id
+            WHERE pt.id = $1 AND dp.user_id = $2
             "#,
-            id
+            id,
+            user_id
         )
-        .fetch_one(pool)
+        .fetch_optional(pool)
         .await?;
 
         Ok(todo)
@@ -171,8 +183,8 @@ impl ProgressRepo {
         pool: &PgPool,
         id: &Uuid,
         user_id: &Uuid,
-    ) -> Result<DailyProgressTodo> {
-        let todo: DailyProgressTodo = sqlx::query_as!(
+    ) -> Result<Option<DailyProgressTodo>> {
+        let todo = sqlx::query_as!(
             DailyProgressTodo,
             r#"
             UPDATE daily_progress_todos dpt
@@ -186,7 +198,7 @@ impl ProgressRepo {
             id,
             user_id
         )
-        .fetch_one(pool)
+        .fetch_optional(pool)
         .await?;
 
         Ok(todo)
@@ -195,6 +207,7 @@ impl ProgressRepo {
     pub async fn fetch_all_daily_progress_todos(
         pool: &PgPool,
         daily_progress_id: &Uuid,
+        user_id: &Uuid,
     ) -> Result<Vec<CompleteDailyProgressTodo>> {
         let todos = sqlx::query_as!(
             CompleteDailyProgressTodo,
@@ -207,16 +220,19 @@ impl ProgressRepo {
             td.title AS todo_title,
             td.description AS todo_description,
             c.slug AS category_slug,
-            c.name AS category_name
+            c.name AS category_name,
+            td.priority AS "priority: Priority",
+            td.estimated_minutes
 
   
         FROM daily_progress_todos t
         JOIN todos td ON td.id = t.todo_id
         JOIN categories c ON c.id = td.category_id
-        WHERE t.daily_progress_id = $1
-        ORDER BY t.created_at DESC
+        WHERE t.daily_progress_id = $1 AND td.user_id = $2
+        ORDER BY td.priority, t.created_at DESC
         "#,
-            daily_progress_id
+            daily_progress_id,
+            user_id
         )
         .fetch_all(pool)
         .await?;
@@ -246,24 +262,111 @@ impl ProgressRepo {
         Ok(progress_id)
     }
 
-    pub async fn delete_daily_progress_todo(pool: &PgPool, id: &Uuid) -> Result<(), AppError> {
+    // false when the progress todo doesn't exist or belongs to someone else
+    pub async fn delete_daily_progress_todo(pool: &PgPool, id: &Uuid, user_id: &Uuid) -> Result<bool> {
         let result = sqlx::query!(
             r#"
             WITH deleted_dpt AS (
-                DELETE FROM daily_progress_todos
-                WHERE id = $1
-                RETURNING todo_id
+                DELETE FROM daily_progress_todos dpt
+                USING daily_progress dp
+                WHERE dpt.id = $1
+                    AND dp.id = dpt.daily_progress_id
+                    AND dp.user_id = $2
+                RETURNING dpt.todo_id
             )
             DELETE FROM todos
             WHERE id = (SELECT todo_id FROM deleted_dpt)
             "#,
-            id
+            id,
+            user_id
         ).execute(pool).await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::Failed("Failed to delete todo".into()));
-        }
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn is_owner(pool: &PgPool, daily_progress_id: &Uuid, user_id: &Uuid) -> Result<bool> {
+        let owned = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM daily_progress
+                WHERE id = $1 AND user_id = $2
+            ) AS "owned!"
+            "#,
+            daily_progress_id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(owned)
+    }
+
+    pub async fn fetch_summaries(
+        pool: &PgPool,
+        user_id: &Uuid,
+        page: &Page,
+    ) -> Result<Vec<DailyProgressSummary>> {
+        let summaries = sqlx::query_as!(
+            DailyProgressSummary,
+            r#"
+            SELECT
+                dp.day,
+                COUNT(t.id) AS "todos_total!",
+                COUNT(t.id) FILTER (WHERE t.is_done) AS "todos_done!"
+            FROM daily_progress dp
+            LEFT JOIN daily_progress_todos t ON t.daily_progress_id = dp.id
+            WHERE dp.user_id = $1
+            GROUP BY dp.id
+            ORDER BY dp.day DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            page.limit,
+            page.offset
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(summaries)
+    }
+
+    // consecutive active days share the same `day - row_number`, so each group is one streak
+    pub async fn fetch_stats(pool: &PgPool, user_id: &Uuid) -> Result<ProgressStats> {
+        let stats = sqlx::query_as!(
+            ProgressStats,
+            r#"
+            WITH active_days AS (
+                SELECT DISTINCT dp.day
+                FROM daily_progress dp
+                JOIN daily_progress_todos t ON t.daily_progress_id = dp.id
+                WHERE dp.user_id = $1 AND t.is_done
+            ),
+            streaks AS (
+                SELECT MAX(day) AS last_day, COUNT(*) AS length
+                FROM (
+                    SELECT day, day - (ROW_NUMBER() OVER (ORDER BY day))::int AS run
+                    FROM active_days
+                ) runs
+                GROUP BY run
+            )
+            SELECT
+                (SELECT COUNT(*) FROM daily_progress WHERE user_id = $1) AS "days_tracked!",
+                (
+                    SELECT COUNT(*)
+                    FROM daily_progress_todos t
+                    JOIN daily_progress dp ON dp.id = t.daily_progress_id
+                    WHERE dp.user_id = $1 AND t.is_done
+                ) AS "todos_done!",
+                -- still running while the last active day is today or yesterday
+                COALESCE(MAX(length) FILTER (WHERE last_day >= CURRENT_DATE - 1), 0) AS "current_streak!",
+                COALESCE(MAX(length), 0) AS "longest_streak!"
+            FROM streaks
+            "#,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
 
-        Ok(())
+        Ok(stats)
     }
 }
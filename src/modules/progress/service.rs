@@ -4,10 +4,14 @@ use uuid::Uuid;
 
 use crate::{
     common::error::{AppError, NotFoundError},
-    modules::progress::{
-            model::{CompleteDailyProgressTodo, DailyProgress, DailyProgressTodo, DailyProgressTodoDto, DailyProgressTodoResponse, ProgressTodoRespons},
+    modules::{
+        follow::model::Page,
+        mission::repository::MissionRepo,
+        progress::{
+            model::{CompleteDailyProgressTodo, DailyProgress, DailyProgressSummary, DailyProgressTodo, DailyProgressTodoDto, DailyProgressTodoResponse, ProgressStats, ProgressTodoRespons},
             repository::ProgressRepo,
         },
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -25,8 +29,13 @@ impl ProgressService {
         user_id: &Uuid,
         day: Date,
     ) -> Result<DailyProgress, AppError> {
-        let daily_progress = ProgressRepo::create_daily_progress(&self.pool, user_id, day)
-            .await?;
+        let mut tx = self.pool.begin().await?;
+
+        let daily_progress = ProgressRepo::create_daily_progress(&mut tx, user_id, day).await?;
+        // the day starts with the user's recurring missions already in it
+        MissionRepo::populate(&mut tx, day, Some(user_id)).await?;
+
+        tx.commit().await?;
 
         Ok(daily_progress)
     }
@@ -45,18 +54,27 @@ impl ProgressService {
     }
 
     pub async fn toggle_daily_progress_todo(&self, progress_todo_id: &Uuid, user_id: &Uuid) -> Result<DailyProgressTodo, AppError>{
-        let todo = ProgressRepo::toggle_daily_progress_todo(&self.pool, progress_todo_id, user_id).await?;
+        let todo = ProgressRepo::toggle_daily_progress_todo(&self.pool, progress_todo_id, user_id)
+            .await?
+            .ok_or(AppError::NotFound(NotFoundError::ProgressTodoNotFound))?;
 
         Ok(todo)
     }
 
-    pub async fn fetch_all_daily_progress_todo(&self, daily_progress_id: &Uuid) -> Result<Vec<CompleteDailyProgressTodo>, AppError> {
-        let progress_todos= ProgressRepo::fetch_all_daily_progress_todos(&self.pool, daily_progress_id).await?;
+    // someone else's daily progress is reported as missing, not as empty
+    pub async fn fetch_all_daily_progress_todo(&self, daily_progress_id: &Uuid, user_id: &Uuid) -> Result<Vec<CompleteDailyProgressTodo>, AppError> {
+        if !ProgressRepo::is_owner(&self.pool, daily_progress_id, user_id).await? {
+            return Err(AppError::NotFound(NotFoundError::DailyProgressNotFound));
+        }
+
+        let progress_todos= ProgressRepo::fetch_all_daily_progress_todos(&self.pool, daily_progress_id, user_id).await?;
         Ok(progress_todos)
     }
 
-    pub async fn fetch_daily_progress_todo_id(&self, progress_todo_id: &Uuid)-> Result<ProgressTodoRespons, AppError> {
-        let task: ProgressTodoRespons = ProgressRepo::fetch_daily_progress_todo_by_id(&self.pool, progress_todo_id).await?;
+    pub async fn fetch_daily_progress_todo_id(&self, progress_todo_id: &Uuid, user_id: &Uuid)-> Result<ProgressTodoRespons, AppError> {
+        let task: ProgressTodoRespons = ProgressRepo::fetch_daily_progress_todo_by_id(&self.pool, progress_todo_id, user_id)
+            .await?
+            .ok_or(AppError::NotFound(NotFoundError::ProgressTodoNotFound))?;
 
         Ok(task)
     }
@@ -67,10 +85,23 @@ impl ProgressService {
         Ok(progress)
     }
 
-    pub async fn delete_daily_progress_todo(&self, id: &Uuid) -> Result<(), AppError> {
-        ProgressRepo::delete_daily_progress_todo(&self.pool, id).await?;
+    pub async fn delete_daily_progress_todo(&self, id: &Uuid, user_id: &Uuid) -> Result<(), AppError> {
+        if !ProgressRepo::delete_daily_progress_todo(&self.pool, id, user_id).await? {
+            return Err(AppError::NotFound(NotFoundError::ProgressTodoNotFound));
+        }
 
         Ok(())
     }
-}
 
+    pub async fn fetch_summaries(&self, user_id: &Uuid, page: Page) -> Result<Vec<DailyProgressSummary>, AppError> {
+        let summaries = ProgressRepo::fetch_summaries(&self.pool, user_id, &page).await?;
+
+        Ok(summaries)
+    }
+
+    pub async fn fetch_stats(&self, user_id: &Uuid) -> Result<ProgressStats, AppError> {
+        let stats = ProgressRepo::fetch_stats(&self.pool, user_id).await?;
+
+        Ok(stats)
+    }
+}
@@ -6,12 +6,14 @@ use crate::{
         response::ApiResponse,
     },
     modules::{
+        privacy::model::Section,
         rooms::{
             model::{ClientEvent, MessageDto, PresenceKind, RoomDto, ServerEvent},
             repository::RoomRepo,
             service::RoomService,
         },
         user::{model::UserId, repository::UserRepo},
+        verification::model::RestrictedAction,
     },
     state::{AppState, RoomState},
 };
@@ -36,6 +38,11 @@ pub async fn create_room_handler(
 ) -> Result<(StatusCode, Json<ApiResponse<impl serde::Serialize>>), AppError> {
     let room = RoomDto::validate(dto)?;
 
+    state
+        .verification_service
+        .ensure_allowed(&user_id.0, RestrictedAction::CreateRoom)
+        .await?;
+
     let room = RoomRepo::create_room(&state.pool, room, user_id.0).await?;
 
     Ok((
@@ -70,6 +77,24 @@ pub async fn get_all_rooms_handler(
     ))
 }
 
+pub async fn user_rooms_handler(
+    State(state): State<AppState>,
+    user_id: Option<Extension<UserId>>,
+    Path(username): Path<String>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let user = state.user_service.get_user_by_username(&username).await?;
+
+    state
+        .privacy_service
+        .audience(user_id.map(|Extension(user_id)| user_id.0), &user.id)
+        .await?
+        .ensure(Section::RoomMemberships)?;
+
+    let rooms = RoomRepo::get_all_joined_rooms(&state.pool, &user.id).await?;
+
+    Ok(Json(ApiResponse::success("Successfully fetch joined rooms", rooms)))
+}
+
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
@@ -120,7 +145,7 @@ pub async fn handler_socket(socket: WebSocket, state: AppState, room_id: Uuid, u
     let (mut sender, mut receiver) = socket.split();
 
     // send history
-    if let Ok(history) = RoomRepo::load_recent_messages(&state.pool, room_id).await {
+    if let Ok(history) = RoomRepo::load_recent_messages(&state.pool, room_id, &user_id).await {
         if let Ok(send) = serde_json::to_string(&ServerEvent::History(history)) {
             let _ = sender.send(Message::Text(send.into())).await;
         } else {
@@ -163,9 +188,10 @@ pub async fn handler_socket(socket: WebSocket, state: AppState, room_id: Uuid, u
                             if let Ok(saved) =
                                 RoomRepo::create_message(&pool, dto, &user_id, parent_id).await
                             {
-                                RoomService::broadcast_message(
+                                RoomService::broadcast_from(
                                     &state_clone,
                                     &room_id,
+                                    &user_id,
                                     ServerEvent::ChatMessage(saved),
                                 )
                                 .await;
@@ -177,9 +203,10 @@ pub async fn handler_socket(socket: WebSocket, state: AppState, room_id: Uuid, u
                             .await;
                     }
                     ClientEvent::Typing { is_typing } => {
-                        RoomService::broadcast_message(
+                        RoomService::broadcast_from(
                             &state_clone,
                             &room_id,
+                            &user_id,
                             ServerEvent::Typing {
                                 username: username_clone.clone(),
                                 is_typing,
@@ -235,6 +262,11 @@ pub async fn join_room_handler(
     Extension(user_id): Extension<UserId>,
     Path(room_id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    state
+        .verification_service
+        .ensure_allowed(&user_id.0, RestrictedAction::JoinRoom)
+        .await?;
+
     state.room_service.join_room(&room_id, &user_id.0).await?;
 
     Ok(Json(ApiResponse::success(
@@ -271,9 +303,10 @@ pub async fn get_room_membership_handler(
 
 pub async fn get_room_members_handler(
     State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
     Path(room_id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<ApiResponse<impl serde::Serialize>>), AppError> {
-    let room_members = RoomService::get_room_messages(state.room_service, room_id).await?;
+    let room_members = RoomService::get_room_messages(state.room_service, room_id, &user_id.0).await?;
 
     Ok((
         StatusCode::OK,
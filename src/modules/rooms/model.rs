@@ -56,7 +56,12 @@ pub enum ServerEvent {
     Presence { user: String, kind: PresenceKind },
     Pong,
     Typing {username: String, is_typing: bool},
-    ActiveMembers (Vec<Members>)
+    ActiveMembers (Vec<Members>),
+    MessageDeleted { id: Uuid },
+    RoomClosed,
+    // last event before the server drops a suspended user's socket, `until` is a unix timestamp
+    AccountSuspended { reason: String, until: i64 },
+    AccountDeleted,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -125,23 +125,31 @@ impl RoomRepo {
     pub async fn load_recent_messages(
         pool: &PgPool,
         room_id: Uuid,
+        viewer_id: &Uuid,
     ) -> Result<Vec<MessageResponse>> {
         let message: Vec<MessageResponse> = sqlx::query_as!(
             MessageResponse,
             r#"
         SELECT 
             m.id,
-            u.username as "user_name!",
+            COALESCE(CASE WHEN u.deleted_at IS NULL THEN u.username END, 'deleted user') as "user_name!",
             m.content,
             m.created_at,
             m.parent_id
         FROM user_messages m
-        JOIN users u ON m.user_id = u.id
+        -- the author is hidden once their account is deleted, and gone after the purge
+        LEFT JOIN users u ON m.user_id = u.id
         WHERE m.room_id = $1
+            -- authors the viewer blocked are left out of their history
+            AND NOT EXISTS (
+                SELECT 1 FROM blocks b
+                WHERE b.blocker_id = $2 AND b.blocked_id = m.user_id
+            )
         ORDER BY m.created_at DESC
         LIMIT 50
             "#,
-            room_id
+            room_id,
+            viewer_id
         )
         .fetch_all(pool)
         .await?;
@@ -215,7 +223,7 @@ impl RoomRepo {
             SELECT m.user_id, u.name, u.username
             FROM members m
             JOIN users u ON u.id = m.user_Id
-            WHERE room_id = $1
+            WHERE room_id = $1 AND u.deleted_at IS NULL
             "#,
             room_id
         ).fetch_all(pool).await?;
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use sqlx::PgPool;
 use tokio::sync::mpsc;
@@ -11,7 +11,7 @@ use crate::{
     modules::{rooms::{
         model::{Members, MessageDto, MessageResponse, PresenceKind, ServerEvent},
         repository::RoomRepo,
-    }, user::repository::UserRepo},
+    }, block::repository::BlockRepo, user::repository::UserRepo},
     state::{AppState, Member, RoomState},
 };
 
@@ -38,8 +38,8 @@ impl RoomService {
         Ok(message)
     }
 
-    pub async fn get_room_messages(self, room_id: Uuid) -> Result<Vec<MessageResponse>, AppError> {
-        let message = RoomRepo::load_recent_messages(&self.pool, room_id).await?;
+    pub async fn get_room_messages(self, room_id: Uuid, viewer_id: &Uuid) -> Result<Vec<MessageResponse>, AppError> {
+        let message = RoomRepo::load_recent_messages(&self.pool, room_id, viewer_id).await?;
         Ok(message)
     }
 
@@ -72,10 +72,33 @@ impl RoomService {
         username: String,
         tx: mpsc::Sender<ServerEvent>,
     ) {
+        // without the list the member still gets everything, missing messages is worse
+        let blocked = match BlockRepo::fetch_blocked_ids(&state.pool, &user_id).await {
+            Ok(blocked) => blocked.into_iter().collect(),
+            Err(err) => {
+                eprintln!("Failed to fetch users blocked by {user_id}: {err}");
+                HashSet::new()
+            }
+        };
+
         let mut rooms = state.rooms.lock().await;
         let room = rooms.entry(room_id).or_insert(RoomState {members: HashMap::new()});
 
-        room.members.insert(user_id, Member {username, tx});
+        room.members.insert(user_id, Member {username, tx, blocked});
+    }
+
+    // keeps the block lists of the user's open sockets in sync with the blocks table
+    pub async fn set_blocked(state: &AppState, blocker_id: &Uuid, blocked_id: &Uuid, blocked: bool) {
+        let mut rooms = state.rooms.lock().await;
+        for room in rooms.values_mut() {
+            if let Some(member) = room.members.get_mut(blocker_id) {
+                if blocked {
+                    member.blocked.insert(*blocked_id);
+                } else {
+                    member.blocked.remove(blocked_id);
+                }
+            }
+        }
     }
 
     pub async fn unregister_member(state: &AppState, room_id: Uuid, user_id: &Uuid) {
@@ -104,6 +127,19 @@ impl RoomService {
         }
     }
 
+    // like broadcast_message, but members who blocked `sender_id` don't receive it
+    pub async fn broadcast_from(state: &AppState, room_id: &Uuid, sender_id: &Uuid, server_event: ServerEvent) {
+        let rooms = state.rooms.lock().await;
+        if let Some(room) = rooms.get(room_id) {
+            for m in room.members.values() {
+                if m.blocked.contains(sender_id) {
+                    continue;
+                }
+                let _ = m.tx.send(server_event.clone()).await;
+            }
+        }
+    }
+
     pub async fn get_active_members(state: &AppState, room_id: &Uuid) -> Result<Vec<Members>, AppError> {
         let rooms = state.rooms.lock().await;
         let mut members: Vec<Username> = Vec::new();
@@ -126,4 +162,24 @@ impl RoomService {
             }
         }
     }
-}
\ No newline at end of file
+
+    // dropping the member senders ends every socket of the room
+    pub async fn close_room(state: &AppState, room_id: &Uuid) {
+        let mut rooms = state.rooms.lock().await;
+        if let Some(room) = rooms.remove(room_id) {
+            for m in room.members.values() {
+                let _ = m.tx.send(ServerEvent::RoomClosed).await;
+            }
+        }
+    }
+
+    // sends `server_event` to every socket the user has open, then drops them
+    pub async fn disconnect_user(state: &AppState, user_id: &Uuid, server_event: ServerEvent) {
+        let mut rooms = state.rooms.lock().await;
+        for room in rooms.values_mut() {
+            if let Some(member) = room.members.remove(user_id) {
+                let _ = member.tx.send(server_event.clone()).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    response::IntoResponse,
+};
+use axum_extra::extract::CookieJar;
+use time::Duration;
+use tower_cookies::Cookie;
+use uuid::Uuid;
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    modules::{
+        session::{
+            model::{IssuedSession, SessionId},
+            service::REFRESH_TOKEN_TTL_DAYS,
+        },
+        user::model::UserId,
+    },
+    state::AppState,
+};
+
+pub const REFRESH_COOKIE: &str = "refresh_token";
+const REFRESH_COOKIE_PATH: &str = "/api/user/refresh";
+
+pub fn add_auth_cookies(jar: CookieJar, jwt: String, session: IssuedSession) -> CookieJar {
+    jar.add(Cookie::build(("jwt", jwt)).http_only(true).path("/"))
+        .add(
+            Cookie::build((REFRESH_COOKIE, session.refresh_token))
+                .http_only(true)
+                .path(REFRESH_COOKIE_PATH)
+                .max_age(Duration::days(REFRESH_TOKEN_TTL_DAYS)),
+        )
+}
+
+pub fn remove_auth_cookies(jar: CookieJar) -> CookieJar {
+    jar.remove(
+        Cookie::build(("jwt", ""))
+            .http_only(true)
+            .path("/")
+            .max_age(Duration::seconds(0)),
+    )
+    .remove(
+        Cookie::build((REFRESH_COOKIE, ""))
+            .http_only(true)
+            .path(REFRESH_COOKIE_PATH)
+            .max_age(Duration::seconds(0)),
+    )
+}
+
+pub async fn list_sessions_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Extension(session_id): Extension<SessionId>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let sessions = state
+        .session_service
+        .list_active(&user_id.0, &session_id.0)
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        "Sessions fetch successfuly",
+        sessions,
+    )))
+}
+
+pub async fn revoke_session_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Extension(current_session): Extension<SessionId>,
+    Path(session_id): Path<Uuid>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AppError> {
+    state.session_service.revoke(&session_id, &user_id.0).await?;
+
+    // revoking the session in use is the same as logging out
+    let jar = if session_id == current_session.0 {
+        remove_auth_cookies(jar)
+    } else {
+        jar
+    };
+
+    Ok((
+        jar,
+        Json(ApiResponse::success("Session revoked successfuly", None::<()>)),
+    ))
+}
+
+pub async fn revoke_all_sessions_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AppError> {
+    state
+        .session_service
+        .revoke_all_for_user(&user_id.0, None)
+        .await?;
+
+    let jar = remove_auth_cookies(jar);
+
+    Ok((
+        jar,
+        Json(ApiResponse::success(
+            "Logged out from all devices successfuly",
+            None::<()>,
+        )),
+    ))
+}
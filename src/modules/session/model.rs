@@ -0,0 +1,56 @@
+use serde::Serialize;
+use sqlx::prelude::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::user::model::Role;
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: OffsetDateTime,
+    pub expires_at: OffsetDateTime,
+    pub revoked_at: Option<OffsetDateTime>,
+    pub last_seen_at: OffsetDateTime,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+// what the auth middleware needs to know about the account behind a session
+#[derive(Debug, FromRow)]
+pub struct SessionOwner {
+    pub role: Role,
+    pub suspended_until: Option<OffsetDateTime>,
+    pub suspension_reason: Option<String>,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+pub struct ActiveSession {
+    pub id: Uuid,
+    pub created_at: OffsetDateTime,
+    pub last_seen_at: OffsetDateTime,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub is_current: bool,
+}
+
+#[derive(Debug, FromRow)]
+pub struct RefreshTokenRecord {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub used_at: Option<OffsetDateTime>,
+    pub expires_at: OffsetDateTime,
+    pub revoked_at: Option<OffsetDateTime>,
+}
+
+// returned to the handler once; the raw refresh token is never persisted
+pub struct IssuedSession {
+    pub session_id: Uuid,
+    pub user_id: Uuid,
+    pub refresh_token: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SessionId(pub Uuid);
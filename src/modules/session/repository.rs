@@ -0,0 +1,221 @@
+use sqlx::{PgPool, Result};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, AuthError, NotFoundError},
+    middleware::client_info::ClientInfo,
+    modules::{
+        session::model::{ActiveSession, RefreshTokenRecord, Session, SessionOwner},
+        user::model::Role,
+    },
+};
+
+pub struct SessionRepo;
+
+impl SessionRepo {
+    pub async fn create(
+        pool: &PgPool,
+        user_id: &Uuid,
+        token_hash: &str,
+        expires_at: OffsetDateTime,
+        client: &ClientInfo,
+    ) -> Result<Session> {
+        let mut tx = pool.begin().await?;
+
+        let session = sqlx::query_as!(
+            Session,
+            r#"
+            INSERT INTO sessions (user_id, expires_at, user_agent, ip)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, user_id, created_at, expires_at, revoked_at, last_seen_at, user_agent, ip
+            "#,
+            user_id,
+            expires_at,
+            client.user_agent,
+            client.ip
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO refresh_tokens (session_id, token_hash)
+            VALUES ($1, $2)
+            "#,
+            session.id,
+            token_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(session)
+    }
+
+    pub async fn rotate(
+        pool: &PgPool,
+        token_hash: &str,
+        new_token_hash: &str,
+        expires_at: OffsetDateTime,
+        client: &ClientInfo,
+    ) -> Result<RefreshTokenRecord, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let record = sqlx::query_as!(
+            RefreshTokenRecord,
+            r#"
+            SELECT rt.id, rt.session_id, rt.used_at, s.user_id, s.expires_at, s.revoked_at
+            FROM refresh_tokens rt
+            JOIN sessions s ON s.id = rt.session_id
+            WHERE rt.token_hash = $1
+            FOR UPDATE
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::Unauthorized(AuthError::InvalidRefreshToken))?;
+
+        if record.revoked_at.is_some() || record.expires_at <= OffsetDateTime::now_utc() {
+            return Err(AppError::Unauthorized(AuthError::SessionRevoked));
+        }
+
+        // a rotated token coming back means it leaked, kill the whole session
+        if record.used_at.is_some() {
+            sqlx::query!(
+                "UPDATE sessions SET revoked_at = now() WHERE id = $1",
+                record.session_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            return Err(AppError::Unauthorized(AuthError::RefreshTokenReused));
+        }
+
+        sqlx::query!(
+            "UPDATE refresh_tokens SET used_at = now() WHERE id = $1",
+            record.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO refresh_tokens (session_id, token_hash)
+            VALUES ($1, $2)
+            "#,
+            record.session_id,
+            new_token_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE sessions
+            SET expires_at = $1, last_seen_at = now(), user_agent = $2, ip = $3
+            WHERE id = $4
+            "#,
+            expires_at,
+            client.user_agent,
+            client.ip,
+            record.session_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    // checks the session is still usable and records activity in the same round trip
+    // returns the owner's current role and standing, None once the session is revoked or expired
+    pub async fn touch(pool: &PgPool, session_id: &Uuid) -> Result<Option<SessionOwner>, AppError> {
+        let owner = sqlx::query_as!(
+            SessionOwner,
+            r#"
+            UPDATE sessions s
+            SET last_seen_at = now()
+            FROM users u
+            WHERE s.id = $1
+                AND u.id = s.user_id
+                AND u.deleted_at IS NULL
+                AND s.revoked_at IS NULL
+                AND s.expires_at > now()
+            RETURNING u.role AS "role: Role", u.suspended_until, u.suspension_reason
+            "#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(owner)
+    }
+
+    pub async fn fetch_active_for_user(
+        pool: &PgPool,
+        user_id: &Uuid,
+        current_session_id: &Uuid,
+    ) -> Result<Vec<ActiveSession>> {
+        let sessions = sqlx::query_as!(
+            ActiveSession,
+            r#"
+            SELECT id, created_at, last_seen_at, user_agent, ip, id = $2 AS "is_current!"
+            FROM sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > now()
+            ORDER BY last_seen_at DESC
+            "#,
+            user_id,
+            current_session_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    pub async fn revoke(pool: &PgPool, session_id: &Uuid, user_id: &Uuid) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE sessions
+            SET revoked_at = now()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+            session_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(NotFoundError::SessionNotFound));
+        }
+
+        Ok(())
+    }
+
+    pub async fn revoke_all_for_user(
+        pool: &PgPool,
+        user_id: &Uuid,
+        except: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE sessions
+            SET revoked_at = now()
+            WHERE user_id = $1 AND revoked_at IS NULL AND id IS DISTINCT FROM $2
+            "#,
+            user_id,
+            except
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    common::error::AppError,
+    middleware::client_info::ClientInfo,
+    modules::session::{
+        model::{ActiveSession, IssuedSession, SessionOwner},
+        repository::SessionRepo,
+    },
+    utils::token::{generate_token, hash_token},
+};
+
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+#[derive(Debug, Clone)]
+pub struct SessionService {
+    pool: PgPool,
+}
+
+impl SessionService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn expires_at() -> OffsetDateTime {
+        OffsetDateTime::now_utc() + Duration::days(REFRESH_TOKEN_TTL_DAYS)
+    }
+
+    pub async fn start(
+        &self,
+        user_id: &Uuid,
+        client: &ClientInfo,
+    ) -> Result<IssuedSession, AppError> {
+        let refresh_token = generate_token();
+
+        let session = SessionRepo::create(
+            &self.pool,
+            user_id,
+            &hash_token(&refresh_token),
+            Self::expires_at(),
+            client,
+        )
+        .await?;
+
+        Ok(IssuedSession {
+            session_id: session.id,
+            user_id: session.user_id,
+            refresh_token,
+        })
+    }
+
+    pub async fn refresh(
+        &self,
+        refresh_token: &str,
+        client: &ClientInfo,
+    ) -> Result<IssuedSession, AppError> {
+        let new_refresh_token = generate_token();
+
+        let record = SessionRepo::rotate(
+            &self.pool,
+            &hash_token(refresh_token),
+            &hash_token(&new_refresh_token),
+            Self::expires_at(),
+            client,
+        )
+        .await?;
+
+        Ok(IssuedSession {
+            session_id: record.session_id,
+            user_id: record.user_id,
+            refresh_token: new_refresh_token,
+        })
+    }
+
+    pub async fn touch(&self, session_id: &Uuid) -> Result<Option<SessionOwner>, AppError> {
+        SessionRepo::touch(&self.pool, session_id).await
+    }
+
+    pub async fn list_active(
+        &self,
+        user_id: &Uuid,
+        current_session_id: &Uuid,
+    ) -> Result<Vec<ActiveSession>, AppError> {
+        let sessions =
+            SessionRepo::fetch_active_for_user(&self.pool, user_id, current_session_id).await?;
+
+        Ok(sessions)
+    }
+
+    pub async fn revoke(&self, session_id: &Uuid, user_id: &Uuid) -> Result<(), AppError> {
+        SessionRepo::revoke(&self.pool, session_id, user_id).await
+    }
+
+    pub async fn revoke_all_for_user(
+        &self,
+        user_id: &Uuid,
+        except: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        SessionRepo::revoke_all_for_user(&self.pool, user_id, except).await
+    }
+}
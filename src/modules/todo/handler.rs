@@ -1,18 +1,20 @@
 use axum::{
     Extension, Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::StatusCode,
 };
 
 use axum_macros::debug_handler;
+use time::{Date, OffsetDateTime, format_description::well_known::Iso8601};
 use uuid::Uuid;
 
 use crate::{
-    common::{error::AppError, response::ApiResponse},
+    common::{error::{AppError, ValidationError}, response::ApiResponse},
     modules::{
         todo::{
             model::{
-                CreateCategoryDto, CreateTagDto, CreateTodoDto, NewTodo,
-                TodoResponse, UpdateTodoCredentials,
+                CreateCategoryDto, CreateTagDto, CreateTodoDto, DueFilter, DueQuery, NewTodo,
+                TodoChanges, UpdateTodoDto,
             },
             service::TodoService,
         },
@@ -21,79 +23,92 @@ use crate::{
     state::AppState,
 };
 
-// #[debug_handler]
-// pub async fn create_todo_handler(
-//     State(state): State<AppState>,
-//     Extension(user_id): Extension<UserId>,
-//     Json(dto): Json<CreateTodoDto>,
-// ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
-//     let new_todo: NewTodo = dto.try_into()?;
-//     let mut tags: Vec<CreateTagDto> = Vec::new();
-    
-//     let todo = state.todo_service.create_todo(user_id.0, &new_todo).await?;
-//     // let daily_progress_todo  
-
-//     for i in new_todo.tags {
-//         let tag = state.todo_service.fetch_tag_slug(user_id.0, &i).await?;
-        
-//         state.todo_service.create_tag_todo(&todo.id, &tag.id).await?;
-        
-//         let create_dto = CreateTagDto {
-//             name: tag.name,
-//             slug: tag.slug
-//         };
-
-//         tags.push(create_dto);
-//     }
-
-//     let category = state.todo_service.fetch_category(&todo.category_id).await?;
-
-//     let todo_response = TodoResponse {
-//         id: todo.id,
-//         title: todo.title,
-//         description: todo.description,
-//         category: category,
-//         tags: tags,
-//         created_at: todo.created_at,
-//         updated_at: todo.updated_at
-//     };
-
-//     Ok(Json(ApiResponse::success(
-//         "User created Successfully",
-//         todo_response,
-//     )))
-// }
+pub async fn create_todo_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Json(dto): Json<CreateTodoDto>,
+) -> Result<(StatusCode, Json<ApiResponse<impl serde::Serialize>>), AppError> {
+    let new_todo: NewTodo = dto.try_into()?;
 
-pub async fn delete_todo_handler(
+    let todo = state.todo_service.create_todo(&user_id.0, new_todo).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success("Todo created successfuly", todo)),
+    ))
+}
+
+pub async fn fetch_all_todos_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let todos = state.todo_service.fetch_all(&user_id.0).await?;
+
+    Ok(Json(ApiResponse::success("All todos fetch successfuly", todos)))
+}
+
+pub async fn get_todo_handler(
     State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
     Path(todo_id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
-    state.todo_service.delete(todo_id).await?;
+    let todo = state.todo_service.get(&user_id.0, &todo_id).await?;
 
-    Ok(Json(ApiResponse::success(
-        "Todo deleted successfuly",
-        None::<()>,
-    )))
+    Ok(Json(ApiResponse::success("Todo fetch successfuly", todo)))
 }
 
-// pub async fn get_todo_handler(
-//     State(state): State<AppState>,
-//     Path(todo_id): Path<Uuid>,
-// ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
-//     let todo = state.todo_service.get(todo_id).await?;
+pub async fn overdue_todos_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Query(query): Query<DueQuery>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let todos = state.todo_service.due(&user_id.0, DueFilter::Overdue, today(query)?).await?;
 
-//     Ok(Json(ApiResponse::success("Todo fetch successfuly", todo)))
-// }
+    Ok(Json(ApiResponse::success("Overdue todos fetch successfuly", todos)))
+}
 
-#[debug_handler]
-pub async fn update_todo_handler(
+pub async fn due_today_todos_handler(
     State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Query(query): Query<DueQuery>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let todos = state.todo_service.due(&user_id.0, DueFilter::Today, today(query)?).await?;
+
+    Ok(Json(ApiResponse::success("Todos due today fetch successfuly", todos)))
+}
+
+fn today(query: DueQuery) -> Result<Date, AppError> {
+    match query.day {
+        Some(day) => Date::parse(&day, &Iso8601::DATE)
+            .map_err(|_| AppError::Validation(ValidationError::InvalidDay)),
+        None => Ok(OffsetDateTime::now_utc().date()),
+    }
+}
+
+pub async fn patch_todo_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(todo_id): Path<Uuid>,
+    Json(dto): Json<UpdateTodoDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let changes: TodoChanges = dto.try_into()?;
+
+    let todo = state.todo_service.patch(&user_id.0, &todo_id, changes).await?;
+
+    Ok(Json(ApiResponse::success("Todo updated successfuly", todo)))
+}
+
+pub async fn delete_todo_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
     Path(todo_id): Path<Uuid>,
-    Json(update): Json<UpdateTodoCredentials>,
 ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
-     state.todo_service.update(update, todo_id).await?;
+    state.todo_service.delete(&user_id.0, &todo_id).await?;
 
-    Ok(Json(ApiResponse::success("Todo fetch successfuly", None::<()>)))
+    Ok(Json(ApiResponse::success(
+        "Todo deleted successfuly",
+        None::<()>,
+    )))
 }
 
 #[debug_handler]
@@ -1,10 +1,35 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::prelude::FromRow;
-use time::PrimitiveDateTime;
+use time::{Date, PrimitiveDateTime, Time};
 use uuid::Uuid;
 
 use crate::{common::error::{AppError, ValidationError}};
 
+time::serde::format_description!(pub due_date, Date, "[year]-[month]-[day]");
+time::serde::format_description!(pub due_time, Time, "[hour]:[minute]");
+
+// P1 is the most urgent, the derived order sorts the same way
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "text")]
+pub enum Priority {
+    P1,
+    P2,
+    P3,
+    #[default]
+    P4,
+}
+
+impl Priority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::P1 => "P1",
+            Priority::P2 => "P2",
+            Priority::P3 => "P3",
+            Priority::P4 => "P4",
+        }
+    }
+}
+
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct Todo {
     pub id: Uuid,
@@ -12,16 +37,10 @@ pub struct Todo {
     pub category_id: Uuid,
     pub title: String,
     pub description: String,
-    pub created_at: PrimitiveDateTime,
-    pub updated_at: PrimitiveDateTime
-}
-
-#[derive(Debug, Clone, Serialize, FromRow)]
-pub struct TodoCred {
-    pub id: Uuid,
-    pub title: String,
-    pub description: String,
-    pub category_id: Uuid,
+    pub priority: Priority,
+    pub due_on: Option<Date>,
+    pub due_time: Option<Time>,
+    pub estimated_minutes: Option<i32>,
     pub created_at: PrimitiveDateTime,
     pub updated_at: PrimitiveDateTime
 }
@@ -33,6 +52,12 @@ pub struct TodoResponse {
     pub description: String,
     pub category: CreateCategoryDto,
     pub tags: Vec<CreateTagDto>,
+    pub priority: Priority,
+    #[serde(with = "due_date::option")]
+    pub due_on: Option<Date>,
+    #[serde(with = "due_time::option")]
+    pub due_time: Option<Time>,
+    pub estimated_minutes: Option<i32>,
     pub created_at: PrimitiveDateTime,
     pub updated_at: PrimitiveDateTime
 }
@@ -83,8 +108,56 @@ pub struct TagTodo {
 pub struct NewTodo {
     pub todo: String,
     pub description: String,
-    pub category_id: Uuid,
-    pub tags: Vec<String>
+    pub category_slug: String,
+    pub tags: Vec<String>,
+    pub priority: Priority,
+    pub due_on: Option<Date>,
+    pub due_time: Option<Time>,
+    pub estimated_minutes: Option<i32>,
+}
+
+// a todo joined with its category, tags are loaded separately
+#[derive(FromRow)]
+pub struct TodoWithCategory {
+    pub id: Uuid,
+    pub title: String,
+    pub description: String,
+    pub category_name: String,
+    pub category_slug: String,
+    pub priority: Priority,
+    pub due_on: Option<Date>,
+    pub due_time: Option<Time>,
+    pub estimated_minutes: Option<i32>,
+    pub created_at: PrimitiveDateTime,
+    pub updated_at: PrimitiveDateTime
+}
+
+#[derive(FromRow)]
+pub struct TodoTag {
+    pub todo_id: Uuid,
+    pub name: String,
+    pub slug: String
+}
+
+impl TodoWithCategory {
+    pub fn into_response(self, tags: Vec<CreateTagDto>) -> TodoResponse {
+        TodoResponse {
+            id: self.id,
+            title: self.title,
+            description: self.description,
+            category: CreateCategoryDto {
+                name: self.category_name,
+                slug: self.category_slug,
+            },
+            tags,
+            priority: self.priority,
+            due_on: self.due_on,
+            due_time: self.due_time,
+            estimated_minutes: self.estimated_minutes,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
 }
 
 pub struct TodoDto {
@@ -93,20 +166,67 @@ pub struct TodoDto {
     pub category_id: Uuid,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct UpdateTodoCredentials {
-    pub todo: Option<String>,
-    pub description: Option<String>,
-}
-
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateTodoDto {
     pub todo: String,
     pub description: String,
-    pub is_done: bool,
+    #[serde(default)]
     pub tags_slug: Vec<String>, 
     pub category_slug: String,
-    pub category_id: Uuid
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default, with = "due_date::option")]
+    pub due_on: Option<Date>,
+    #[serde(default, with = "due_time::option")]
+    pub due_time: Option<Time>,
+    pub estimated_minutes: Option<i32>,
+}
+
+// fields left out stay as they are, `tags_slug` replaces every tag of the todo
+// while `add_tags` / `remove_tags` edit the current ones, a null due date or
+// estimate clears it
+#[derive(Debug, Deserialize)]
+pub struct UpdateTodoDto {
+    pub todo: Option<String>,
+    pub description: Option<String>,
+    pub category_slug: Option<String>,
+    pub tags_slug: Option<Vec<String>>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    pub priority: Option<Priority>,
+    #[serde(default, deserialize_with = "nullable_due_date")]
+    pub due_on: Option<Option<Date>>,
+    #[serde(default, deserialize_with = "nullable_due_time")]
+    pub due_time: Option<Option<Time>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub estimated_minutes: Option<Option<i32>>,
+}
+
+pub struct TodoChanges {
+    pub todo: Option<String>,
+    pub description: Option<String>,
+    pub category_slug: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    pub priority: Option<Priority>,
+    pub due_on: Option<Option<Date>>,
+    pub due_time: Option<Option<Time>>,
+    pub estimated_minutes: Option<Option<i32>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DueFilter {
+    Overdue,
+    Today,
+}
+
+// `day` is the caller's today, the server's UTC date when left out
+#[derive(Debug, Deserialize)]
+pub struct DueQuery {
+    pub day: Option<String>,
 }
 
 impl CreateTagDto {
@@ -162,11 +282,122 @@ impl TryFrom<CreateTodoDto> for NewTodo {
             return Err(ValidationError::DescriptionTooShort);
         };
 
-        return Ok(Self {
+        if value.due_time.is_some() && value.due_on.is_none() {
+            return Err(ValidationError::DueTimeWithoutDate);
+        }
+
+        if value.estimated_minutes.is_some_and(|minutes| !valid_estimate(minutes)) {
+            return Err(ValidationError::InvalidEstimate);
+        }
+
+        Ok(Self {
             todo: todo.to_string(),
             description: description.to_string(),
-            category_id: value.category_id,
-            tags: value.tags_slug
-        });
+            category_slug: value.category_slug,
+            tags: unique_slugs(value.tags_slug),
+            priority: value.priority,
+            due_on: value.due_on,
+            due_time: value.due_time,
+            estimated_minutes: value.estimated_minutes,
+        })
+    }
+}
+
+impl TryFrom<UpdateTodoDto> for TodoChanges {
+    type Error = ValidationError;
+
+    fn try_from(value: UpdateTodoDto) -> Result<Self, Self::Error> {
+        let todo = value.todo.map(|todo| todo.trim().to_string());
+        let description = value.description.map(|description| description.trim().to_string());
+
+        if todo.as_ref().is_some_and(|todo| todo.len() < 5) {
+            return Err(ValidationError::TodoTooShort);
+        }
+
+        if description.as_ref().is_some_and(|description| description.len() < 5) {
+            return Err(ValidationError::DescriptionTooShort);
+        }
+
+        let add_tags = unique_slugs(value.add_tags);
+        let remove_tags = unique_slugs(value.remove_tags);
+
+        // replacing and editing the tags in one request has no clear order
+        if value.tags_slug.is_some() && !(add_tags.is_empty() && remove_tags.is_empty()) {
+            return Err(ValidationError::ConflictingTagChanges);
+        }
+
+        if add_tags.iter().any(|slug| remove_tags.contains(slug)) {
+            return Err(ValidationError::ConflictingTagChanges);
+        }
+
+        // a time can't be set while the date is being cleared, the opposite clears both
+        if value.due_on == Some(None) && value.due_time.is_some_and(|time| time.is_some()) {
+            return Err(ValidationError::DueTimeWithoutDate);
+        }
+
+        if value.estimated_minutes.flatten().is_some_and(|minutes| !valid_estimate(minutes)) {
+            return Err(ValidationError::InvalidEstimate);
+        }
+
+        let changes = Self {
+            todo,
+            description,
+            category_slug: value.category_slug,
+            tags: value.tags_slug.map(unique_slugs),
+            add_tags,
+            remove_tags,
+            priority: value.priority,
+            due_on: value.due_on,
+            due_time: value.due_time,
+            estimated_minutes: value.estimated_minutes,
+        };
+
+        if changes.is_empty() {
+            return Err(ValidationError::NothingToUpdate);
+        }
+
+        Ok(changes)
+    }
+}
+
+impl TodoChanges {
+    fn is_empty(&self) -> bool {
+        self.todo.is_none()
+            && self.description.is_none()
+            && self.category_slug.is_none()
+            && self.tags.is_none()
+            && self.add_tags.is_empty()
+            && self.remove_tags.is_empty()
+            && self.priority.is_none()
+            && self.due_on.is_none()
+            && self.due_time.is_none()
+            && self.estimated_minutes.is_none()
     }
 }
+
+pub fn valid_estimate(minutes: i32) -> bool {
+    (1..=1440).contains(&minutes)
+}
+
+// tells a field set to null apart from one that was left out
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+fn nullable_due_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<Date>>, D::Error> {
+    due_date::option::deserialize(deserializer).map(Some)
+}
+
+fn nullable_due_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<Time>>, D::Error> {
+    due_time::option::deserialize(deserializer).map(Some)
+}
+
+fn unique_slugs(mut slugs: Vec<String>) -> Vec<String> {
+    slugs.sort();
+    slugs.dedup();
+    slugs
+}
@@ -1,92 +1,305 @@
-use sqlx::{PgPool, Postgres, QueryBuilder, Result};
+use sqlx::{PgPool, Postgres, Result, Transaction};
+use time::Date;
 use uuid::Uuid;
 
 use crate::{
-    common::error::AppError,
+    common::error::{AppError, NotFoundError},
     modules::todo::model::{
-        Category, CreateCategoryDto, CreateTagDto, TagTodo, Tags, TodoCred,
+        Category, CreateCategoryDto, CreateTagDto, NewTodo, Priority, TagTodo, Tags, TodoChanges,
+        TodoTag, TodoWithCategory,
     },
 };
 
 pub struct TodoRepo;
 
 impl TodoRepo {
-    // pub async fn insert(pool: &PgPool, user_id: Uuid, new: &NewTodo) -> Result<Todo> {
-    //     let todo = sqlx::query_as!(
-    //         Todo,
-    //         r#"
-    //     INSERT INTO todos (user_id, title, description, category_id)
-    //     VALUES ($1, $2, $3, $4)
-    //     RETURNING id, user_id, title, description, created_at, category_id, updated_at
-    //     "#,
-    //         user_id,
-    //         new.todo,
-    //         new.description,
-    //         new.category_id
-    //     )
-    //     .fetch_one(pool)
-    //     .await?;
-
-    //     Ok(todo)
-    // }
-
-    // pub async fn fetch(pool: &PgPool, todo_id: Uuid) -> Result<Option<TodoCred>> {
-    //     let todo = sqlx::query_as!(
-    //         TodoCred,
-    //         r#"
-    //     SELECT id, title, description, created_at, category_id, updated_at
-    //     FROM todos
-    //     WHERE id = $1
-    //     "#,
-    //         todo_id
-    //     )
-    //     .fetch_optional(pool)
-    //     .await?;
-
-    //     Ok(todo)
-    // }
-
-    pub async fn delete(pool: &PgPool, todo_id: Uuid) -> Result<(), AppError> {
-        let result = sqlx::query!("DELETE FROM todos WHERE id = $1", todo_id)
-            .execute(pool)
-            .await?;
+    // the todo, its category and its tags are written together or not at all
+    pub async fn insert(pool: &PgPool, user_id: &Uuid, new: &NewTodo) -> Result<Uuid, AppError> {
+        let mut tx = pool.begin().await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::Failed("Failed to delect todo".into()));
-        }
+        let category_id = Self::resolve_category(&mut tx, user_id, &new.category_slug).await?;
+        let tag_ids = Self::resolve_tags(&mut tx, user_id, &new.tags).await?;
 
-        Ok(())
+        let todo_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO todos (user_id, title, description, category_id, priority, due_on, due_time, estimated_minutes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+            user_id,
+            new.todo,
+            new.description,
+            category_id,
+            new.priority as Priority,
+            new.due_on,
+            new.due_time,
+            new.estimated_minutes
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::insert_tag_todos(&mut tx, &todo_id, &tag_ids).await?;
+
+        tx.commit().await?;
+
+        Ok(todo_id)
     }
 
-    pub async fn update(
+    pub async fn fetch(pool: &PgPool, user_id: &Uuid, todo_id: &Uuid) -> Result<Option<TodoWithCategory>> {
+        let todo = sqlx::query_as!(
+            TodoWithCategory,
+            r#"
+            SELECT t.id, t.title, t.description, c.name AS category_name, c.slug AS category_slug,
+                t.priority AS "priority: Priority", t.due_on, t.due_time, t.estimated_minutes,
+                t.created_at, t.updated_at
+            FROM todos t
+            JOIN categories c ON c.id = t.category_id
+            WHERE t.id = $1 AND t.user_id = $2
+            "#,
+            todo_id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(todo)
+    }
+
+    pub async fn fetch_all(pool: &PgPool, user_id: &Uuid) -> Result<Vec<TodoWithCategory>> {
+        let todos = sqlx::query_as!(
+            TodoWithCategory,
+            r#"
+            SELECT t.id, t.title, t.description, c.name AS category_name, c.slug AS category_slug,
+                t.priority AS "priority: Priority", t.due_on, t.due_time, t.estimated_minutes,
+                t.created_at, t.updated_at
+            FROM todos t
+            JOIN categories c ON c.id = t.category_id
+            WHERE t.user_id = $1
+            ORDER BY t.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(todos)
+    }
+
+    // not finished in any daily progress yet and due before `day`
+    pub async fn fetch_overdue(pool: &PgPool, user_id: &Uuid, day: Date) -> Result<Vec<TodoWithCategory>> {
+        let todos = sqlx::query_as!(
+            TodoWithCategory,
+            r#"
+            SELECT t.id, t.title, t.description, c.name AS category_name, c.slug AS category_slug,
+                t.priority AS "priority: Priority", t.due_on, t.due_time, t.estimated_minutes,
+                t.created_at, t.updated_at
+            FROM todos t
+            JOIN categories c ON c.id = t.category_id
+            WHERE t.user_id = $1 AND t.due_on < $2
+            AND NOT EXISTS (
+                SELECT 1 FROM daily_progress_todos dpt
+                WHERE dpt.todo_id = t.id AND dpt.is_done
+            )
+            ORDER BY t.priority, t.due_on, t.due_time NULLS LAST
+            "#,
+            user_id,
+            day
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(todos)
+    }
+
+    pub async fn fetch_due_on(pool: &PgPool, user_id: &Uuid, day: Date) -> Result<Vec<TodoWithCategory>> {
+        let todos = sqlx::query_as!(
+            TodoWithCategory,
+            r#"
+            SELECT t.id, t.title, t.description, c.name AS category_name, c.slug AS category_slug,
+                t.priority AS "priority: Priority", t.due_on, t.due_time, t.estimated_minutes,
+                t.created_at, t.updated_at
+            FROM todos t
+            JOIN categories c ON c.id = t.category_id
+            WHERE t.user_id = $1 AND t.due_on = $2
+            ORDER BY t.priority, t.due_time NULLS LAST, t.created_at
+            "#,
+            user_id,
+            day
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(todos)
+    }
+
+    pub async fn fetch_tags_for(pool: &PgPool, todo_ids: &[Uuid]) -> Result<Vec<TodoTag>> {
+        let tags = sqlx::query_as!(
+            TodoTag,
+            r#"
+            SELECT tt.todo_id, t.name, t.slug
+            FROM tag_todo tt
+            JOIN tags t ON t.id = tt.tag_id
+            WHERE tt.todo_id = ANY($1)
+            ORDER BY t.slug
+            "#,
+            todo_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    // NotFound when the todo isn't the user's, a changed tag list replaces the old one,
+    // removing a tag the todo doesn't have is a no-op
+    pub async fn apply_changes(
         pool: &PgPool,
-        todo_id: Uuid,
-        todo: Option<&str>,
-        description: Option<&str>,
-    ) -> Result<TodoCred> {
-        let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new("UPDATE todos SET");
+        user_id: &Uuid,
+        todo_id: &Uuid,
+        changes: &TodoChanges,
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        let category_id = match &changes.category_slug {
+            Some(slug) => Some(Self::resolve_category(&mut tx, user_id, slug).await?),
+            None => None,
+        };
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE todos
+            SET title = COALESCE($3, title),
+                description = COALESCE($4, description),
+                category_id = COALESCE($5, category_id),
+                priority = COALESCE($6, priority),
+                due_on = CASE WHEN $7 THEN $8 ELSE due_on END,
+                due_time = CASE WHEN $9 THEN $10 WHEN $7 AND $8::date IS NULL THEN NULL ELSE due_time END,
+                estimated_minutes = CASE WHEN $11 THEN $12 ELSE estimated_minutes END,
+                updated_at = now()
+            WHERE id = $1 AND user_id = $2
+            "#,
+            todo_id,
+            user_id,
+            changes.todo,
+            changes.description,
+            category_id,
+            changes.priority as Option<Priority>,
+            changes.due_on.is_some(),
+            changes.due_on.flatten(),
+            changes.due_time.is_some(),
+            changes.due_time.flatten(),
+            changes.estimated_minutes.is_some(),
+            changes.estimated_minutes.flatten()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(NotFoundError::TodoNotFound));
+        }
 
-        let mut separated = qb.separated(", ");
+        if let Some(tags) = &changes.tags {
+            let tag_ids = Self::resolve_tags(&mut tx, user_id, tags).await?;
 
-        if let Some(v) = todo {
-            separated.push("todo = ").push_bind(v);
+            sqlx::query!("DELETE FROM tag_todo WHERE todo_id = $1", todo_id)
+                .execute(&mut *tx)
+                .await?;
+
+            Self::insert_tag_todos(&mut tx, todo_id, &tag_ids).await?;
         }
 
-        if let Some(v) = description {
-            separated.push("description = ").push_bind(v);
+        if !changes.remove_tags.is_empty() {
+            sqlx::query!(
+                r#"
+                DELETE FROM tag_todo tt
+                USING tags t
+                WHERE tt.tag_id = t.id AND tt.todo_id = $1 AND t.user_id = $2 AND t.slug = ANY($3)
+                "#,
+                todo_id,
+                user_id,
+                &changes.remove_tags
+            )
+            .execute(&mut *tx)
+            .await?;
         }
 
-        if todo.is_none() && description.is_none() {
-            return Err(sqlx::Error::Protocol("No field to update".into()));
+        if !changes.add_tags.is_empty() {
+            let tag_ids = Self::resolve_tags(&mut tx, user_id, &changes.add_tags).await?;
+
+            Self::insert_tag_todos(&mut tx, todo_id, &tag_ids).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn resolve_category(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &Uuid,
+        slug: &str,
+    ) -> Result<Uuid, AppError> {
+        sqlx::query_scalar!(
+            "SELECT id FROM categories WHERE slug = $1 AND user_id = $2",
+            slug,
+            user_id
+        )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or(AppError::NotFound(NotFoundError::CategoryNotFound))
+    }
+
+    // `slugs` must be unique, every one of them has to be a tag of the user
+    async fn resolve_tags(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &Uuid,
+        slugs: &[String],
+    ) -> Result<Vec<Uuid>, AppError> {
+        let tag_ids = sqlx::query_scalar!(
+            "SELECT id FROM tags WHERE user_id = $1 AND slug = ANY($2)",
+            user_id,
+            slugs
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+
+        if tag_ids.len() != slugs.len() {
+            return Err(AppError::NotFound(NotFoundError::TagNotFound));
         }
 
-        qb.push(" WHERE id = ").push_bind(todo_id);
+        Ok(tag_ids)
+    }
+
+    async fn insert_tag_todos(
+        tx: &mut Transaction<'_, Postgres>,
+        todo_id: &Uuid,
+        tag_ids: &[Uuid],
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO tag_todo (todo_id, tag_id)
+            SELECT $1, tag_id FROM UNNEST($2::uuid[]) AS tag_id
+            ON CONFLICT DO NOTHING
+            "#,
+            todo_id,
+            tag_ids
+        )
+        .execute(&mut **tx)
+        .await?;
 
-        qb.push("RETURNING id, todo, description, is_done, created_at");
+        Ok(())
+    }
 
-        let updated_todo: TodoCred = qb.build_query_as().fetch_one(pool).await?;
+    pub async fn delete(pool: &PgPool, user_id: &Uuid, todo_id: &Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM todos WHERE id = $1 AND user_id = $2",
+            todo_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
 
-        Ok(updated_todo)
+        Ok(result.rows_affected() > 0)
     }
 
     //tags
@@ -124,7 +337,7 @@ impl TodoRepo {
         Ok(tags)
     }
 
-    pub async fn delete_tag(pool: &PgPool, slug: &str, user_id: Uuid) -> Result<(), AppError> {
+    pub async fn delete_tag(pool: &PgPool, slug: &str, user_id: Uuid) -> Result<bool> {
         let result = sqlx::query!(
             "DELETE FROM tags WHERE slug = $1 AND user_id = $2",
             slug,
@@ -133,11 +346,7 @@ impl TodoRepo {
         .execute(pool)
         .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::Failed("Failed to delete tag".into()));
-        }
-
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
     pub async fn create_categories(
@@ -181,7 +390,7 @@ impl TodoRepo {
         Ok(categories)
     }
 
-    pub async fn delete_categories(pool: &PgPool, slug: &str, user_id: Uuid) -> Result<(), AppError> {
+    pub async fn delete_categories(pool: &PgPool, slug: &str, user_id: Uuid) -> Result<bool> {
         let result = sqlx::query!(
             "DELETE FROM categories WHERE slug = $1 AND user_id = $2",
             slug,
@@ -190,11 +399,7 @@ impl TodoRepo {
         .execute(pool)
         .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::Failed("Failed to delete a category".into()))
-        }
-
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
     pub async fn create_tag_todo(pool: &PgPool, todo_id: &Uuid, tag_id: &Uuid) -> Result<()> {
@@ -1,11 +1,14 @@
+use std::collections::HashMap;
+
 use sqlx::PgPool;
+use time::Date;
 use uuid::Uuid;
 
 use crate::{
-    common::error::{AppError, NotFoundError},
+    common::error::{AppError, NotFoundError, ValidationError},
     modules::todo::{
         model::{
-            Category, CreateCategoryDto, CreateTagDto, NewTodo, TagDtoWithId, TagTodo, Tags, Todo, TodoResponse, UpdateTodoCredentials
+            Category, CreateCategoryDto, CreateTagDto, DueFilter, NewTodo, TagTodo, Tags, TodoChanges, TodoResponse, TodoWithCategory
         },
         repository::TodoRepo,
     },
@@ -20,25 +23,86 @@ impl TodoService {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
-    
-    pub async fn update(
-        &self,
-        update: UpdateTodoCredentials,
-        todo_id: Uuid,
-    ) -> Result<(), AppError> {
-        TodoRepo::update(
-            &self.pool,
-            todo_id,
-            update.todo.as_deref(),
-            update.description.as_deref(),
-        )
-        .await?;
 
-        Ok(())
+    pub async fn create_todo(&self, user_id: &Uuid, new: NewTodo) -> Result<TodoResponse, AppError> {
+        let todo_id = TodoRepo::insert(&self.pool, user_id, &new).await?;
+
+        self.get(user_id, &todo_id).await
     }
 
-    pub async fn delete(&self, todo_id: Uuid) -> Result<(), AppError> {
-        TodoRepo::delete(&self.pool, todo_id).await?;
+    // todos of other users are reported as missing
+    pub async fn get(&self, user_id: &Uuid, todo_id: &Uuid) -> Result<TodoResponse, AppError> {
+        let todo = TodoRepo::fetch(&self.pool, user_id, todo_id)
+            .await?
+            .ok_or(AppError::NotFound(NotFoundError::TodoNotFound))?;
+
+        let mut todos = self.with_tags(vec![todo]).await?;
+
+        todos.pop().ok_or(AppError::NotFound(NotFoundError::TodoNotFound))
+    }
+
+    pub async fn fetch_all(&self, user_id: &Uuid) -> Result<Vec<TodoResponse>, AppError> {
+        let todos = TodoRepo::fetch_all(&self.pool, user_id).await?;
+
+        self.with_tags(todos).await
+    }
+
+    pub async fn due(&self, user_id: &Uuid, filter: DueFilter, day: Date) -> Result<Vec<TodoResponse>, AppError> {
+        let todos = match filter {
+            DueFilter::Overdue => TodoRepo::fetch_overdue(&self.pool, user_id, day).await?,
+            DueFilter::Today => TodoRepo::fetch_due_on(&self.pool, user_id, day).await?,
+        };
+
+        self.with_tags(todos).await
+    }
+
+    pub async fn patch(
+        &self,
+        user_id: &Uuid,
+        todo_id: &Uuid,
+        changes: TodoChanges,
+    ) -> Result<TodoResponse, AppError> {
+        // a time on its own only works when the todo already has a date
+        if changes.due_on.is_none() && changes.due_time.is_some_and(|time| time.is_some()) {
+            let todo = TodoRepo::fetch(&self.pool, user_id, todo_id)
+                .await?
+                .ok_or(AppError::NotFound(NotFoundError::TodoNotFound))?;
+
+            if todo.due_on.is_none() {
+                return Err(AppError::Validation(ValidationError::DueTimeWithoutDate));
+            }
+        }
+
+        TodoRepo::apply_changes(&self.pool, user_id, todo_id, &changes).await?;
+
+        self.get(user_id, todo_id).await
+    }
+
+    // one query for the tags of every todo in the list
+    async fn with_tags(&self, todos: Vec<TodoWithCategory>) -> Result<Vec<TodoResponse>, AppError> {
+        let ids: Vec<Uuid> = todos.iter().map(|todo| todo.id).collect();
+
+        let mut tags: HashMap<Uuid, Vec<CreateTagDto>> = HashMap::new();
+        for tag in TodoRepo::fetch_tags_for(&self.pool, &ids).await? {
+            tags.entry(tag.todo_id).or_default().push(CreateTagDto {
+                name: tag.name,
+                slug: tag.slug,
+            });
+        }
+
+        Ok(todos
+            .into_iter()
+            .map(|todo| {
+                let todo_tags = tags.remove(&todo.id).unwrap_or_default();
+                todo.into_response(todo_tags)
+            })
+            .collect())
+    }
+    
+    pub async fn delete(&self, user_id: &Uuid, todo_id: &Uuid) -> Result<(), AppError> {
+        if !TodoRepo::delete(&self.pool, user_id, todo_id).await? {
+            return Err(AppError::NotFound(NotFoundError::TodoNotFound));
+        }
 
         Ok(())
     }
@@ -56,7 +120,9 @@ impl TodoService {
     }
 
     pub async fn delete_tag(&self, slug: String, user_id: Uuid) -> Result<(), AppError> {
-        TodoRepo::delete_tag(&self.pool, &slug, user_id).await?;
+        if !TodoRepo::delete_tag(&self.pool, &slug, user_id).await? {
+            return Err(AppError::NotFound(NotFoundError::TagNotFound));
+        }
 
         Ok(())
     }
@@ -83,7 +149,10 @@ impl TodoService {
     }
 
     pub async fn delete_category(&self, slug: String, user_id: Uuid) -> Result<(), AppError> {
-        TodoRepo::delete_categories(&self.pool, &slug, user_id).await?;
+        if !TodoRepo::delete_categories(&self.pool, &slug, user_id).await? {
+            return Err(AppError::NotFound(NotFoundError::CategoryNotFound));
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,55 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    modules::{
+        token::model::{CreateTokenDto, NewToken},
+        user::model::UserId,
+        verification::model::RestrictedAction,
+    },
+    state::AppState,
+};
+
+pub async fn create_token_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Json(dto): Json<CreateTokenDto>,
+) -> Result<(StatusCode, Json<ApiResponse<impl serde::Serialize>>), AppError> {
+    let new_token: NewToken = dto.try_into()?;
+
+    state
+        .verification_service
+        .ensure_allowed(&user_id.0, RestrictedAction::CreateToken)
+        .await?;
+
+    let token = state.token_service.create(&user_id.0, new_token).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::success("Token created successfuly", token)),
+    ))
+}
+
+pub async fn fetch_all_tokens_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let tokens = state.token_service.list(&user_id.0).await?;
+
+    Ok(Json(ApiResponse::success("Tokens fetch successfuly", tokens)))
+}
+
+pub async fn revoke_token_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Path(token_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    state.token_service.revoke(&token_id, &user_id.0).await?;
+
+    Ok(Json(ApiResponse::success("Token revoked successfuly", None::<()>)))
+}
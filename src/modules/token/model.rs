@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{common::error::ValidationError, modules::user::model::Role};
+
+pub const TOKEN_PREFIX: &str = "wiki_pat_";
+const MAX_TOKEN_TTL_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Scope {
+    #[serde(rename = "user:read")]
+    UserRead,
+    #[serde(rename = "user:write")]
+    UserWrite,
+    #[serde(rename = "todos:read")]
+    TodosRead,
+    #[serde(rename = "todos:write")]
+    TodosWrite,
+    #[serde(rename = "progress:read")]
+    ProgressRead,
+    #[serde(rename = "progress:write")]
+    ProgressWrite,
+    #[serde(rename = "rooms:read")]
+    RoomsRead,
+    #[serde(rename = "rooms:write")]
+    RoomsWrite,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::UserRead => "user:read",
+            Scope::UserWrite => "user:write",
+            Scope::TodosRead => "todos:read",
+            Scope::TodosWrite => "todos:write",
+            Scope::ProgressRead => "progress:read",
+            Scope::ProgressWrite => "progress:write",
+            Scope::RoomsRead => "rooms:read",
+            Scope::RoomsWrite => "rooms:write",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user:read" => Ok(Scope::UserRead),
+            "user:write" => Ok(Scope::UserWrite),
+            "todos:read" => Ok(Scope::TodosRead),
+            "todos:write" => Ok(Scope::TodosWrite),
+            "progress:read" => Ok(Scope::ProgressRead),
+            "progress:write" => Ok(Scope::ProgressWrite),
+            "rooms:read" => Ok(Scope::RoomsRead),
+            "rooms:write" => Ok(Scope::RoomsWrite),
+            _ => Err(ValidationError::InvalidScope),
+        }
+    }
+}
+
+// a route group, each one is guarded by its `<resource>:read` and `<resource>:write` scopes
+#[derive(Debug, Clone, Copy)]
+pub enum Resource {
+    User,
+    Todos,
+    Progress,
+    Rooms,
+}
+
+impl Resource {
+    pub fn scope(&self, write: bool) -> Scope {
+        match (self, write) {
+            (Resource::User, false) => Scope::UserRead,
+            (Resource::User, true) => Scope::UserWrite,
+            (Resource::Todos, false) => Scope::TodosRead,
+            (Resource::Todos, true) => Scope::TodosWrite,
+            (Resource::Progress, false) => Scope::ProgressRead,
+            (Resource::Progress, true) => Scope::ProgressWrite,
+            (Resource::Rooms, false) => Scope::RoomsRead,
+            (Resource::Rooms, true) => Scope::RoomsWrite,
+        }
+    }
+}
+
+// what the current request is allowed to do; browser sessions are unrestricted
+#[derive(Debug, Clone)]
+pub enum AccessScopes {
+    All,
+    Limited(Vec<Scope>),
+}
+
+impl AccessScopes {
+    pub fn allows(&self, scope: Scope) -> bool {
+        match self {
+            AccessScopes::All => true,
+            AccessScopes::Limited(scopes) => scopes.contains(&scope),
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct PersonalAccessToken {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<OffsetDateTime>,
+    pub last_used_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, FromRow)]
+pub struct TokenOwner {
+    pub user_id: Uuid,
+    pub scopes: Vec<String>,
+    pub role: Role,
+    pub suspended_until: Option<OffsetDateTime>,
+    pub suspension_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub expires_at: Option<OffsetDateTime>,
+    pub last_used_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedTokenResponse {
+    #[serde(flatten)]
+    pub details: TokenResponse,
+    // shown once, only the hash is kept
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTokenDto {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub expires_in_days: Option<i64>,
+}
+
+pub struct NewToken {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub expires_in_days: Option<i64>,
+}
+
+pub fn parse_scopes(scopes: &[String]) -> Vec<Scope> {
+    scopes.iter().filter_map(|s| s.parse().ok()).collect()
+}
+
+impl From<PersonalAccessToken> for TokenResponse {
+    fn from(value: PersonalAccessToken) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            scopes: parse_scopes(&value.scopes),
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl TryFrom<CreateTokenDto> for NewToken {
+    type Error = ValidationError;
+
+    fn try_from(value: CreateTokenDto) -> Result<Self, Self::Error> {
+        let name = value.name.trim();
+
+        if name.is_empty() || name.len() > 64 {
+            return Err(ValidationError::InvalidTokenName);
+        }
+
+        if value.scopes.is_empty() {
+            return Err(ValidationError::InvalidScope);
+        }
+
+        if let Some(days) = value.expires_in_days
+            && !(1..=MAX_TOKEN_TTL_DAYS).contains(&days)
+        {
+            return Err(ValidationError::InvalidTokenExpiry);
+        }
+
+        let mut scopes: Vec<Scope> = Vec::new();
+        for scope in value.scopes {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            scopes,
+            expires_in_days: value.expires_in_days,
+        })
+    }
+}
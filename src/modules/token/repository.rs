@@ -0,0 +1,103 @@
+use sqlx::{PgPool, Result};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, NotFoundError},
+    modules::{
+        token::model::{PersonalAccessToken, TokenOwner},
+        user::model::Role,
+    },
+};
+
+pub struct TokenRepo;
+
+impl TokenRepo {
+    pub async fn create(
+        pool: &PgPool,
+        user_id: &Uuid,
+        name: &str,
+        token_hash: &str,
+        scopes: &[String],
+        expires_at: Option<OffsetDateTime>,
+    ) -> Result<PersonalAccessToken> {
+        let token = sqlx::query_as!(
+            PersonalAccessToken,
+            r#"
+            INSERT INTO personal_access_tokens (user_id, name, token_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, name, scopes, expires_at, last_used_at, created_at
+            "#,
+            user_id,
+            name,
+            token_hash,
+            scopes,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn fetch_all(pool: &PgPool, user_id: &Uuid) -> Result<Vec<PersonalAccessToken>> {
+        let tokens = sqlx::query_as!(
+            PersonalAccessToken,
+            r#"
+            SELECT id, name, scopes, expires_at, last_used_at, created_at
+            FROM personal_access_tokens
+            WHERE user_id = $1 AND revoked_at IS NULL
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    // resolves a presented token and records its use in the same round trip
+    pub async fn touch(pool: &PgPool, token_hash: &str) -> Result<Option<TokenOwner>> {
+        let owner = sqlx::query_as!(
+            TokenOwner,
+            r#"
+            UPDATE personal_access_tokens t
+            SET last_used_at = now()
+            FROM users u
+            WHERE t.token_hash = $1
+                AND u.id = t.user_id
+                AND u.deleted_at IS NULL
+                AND t.revoked_at IS NULL
+                AND (t.expires_at IS NULL OR t.expires_at > now())
+            RETURNING t.user_id, t.scopes, u.role AS "role: Role", u.suspended_until,
+                u.suspension_reason
+            "#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(owner)
+    }
+
+    pub async fn revoke(pool: &PgPool, token_id: &Uuid, user_id: &Uuid) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE personal_access_tokens
+            SET revoked_at = now()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+            token_id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(NotFoundError::TokenNotFound));
+        }
+
+        Ok(())
+    }
+}
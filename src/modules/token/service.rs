@@ -0,0 +1,70 @@
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    common::error::AppError,
+    modules::token::{
+        model::{CreatedTokenResponse, NewToken, TOKEN_PREFIX, TokenOwner, TokenResponse},
+        repository::TokenRepo,
+    },
+    utils::token::{generate_token, hash_token},
+};
+
+#[derive(Debug, Clone)]
+pub struct TokenService {
+    pool: PgPool,
+}
+
+impl TokenService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: &Uuid,
+        new_token: NewToken,
+    ) -> Result<CreatedTokenResponse, AppError> {
+        let token = format!("{TOKEN_PREFIX}{}", generate_token());
+        let scopes: Vec<String> = new_token
+            .scopes
+            .iter()
+            .map(|s| s.as_str().to_string())
+            .collect();
+        let expires_at = new_token
+            .expires_in_days
+            .map(|days| OffsetDateTime::now_utc() + Duration::days(days));
+
+        let created = TokenRepo::create(
+            &self.pool,
+            user_id,
+            &new_token.name,
+            &hash_token(&token),
+            &scopes,
+            expires_at,
+        )
+        .await?;
+
+        Ok(CreatedTokenResponse {
+            details: created.into(),
+            token,
+        })
+    }
+
+    pub async fn list(&self, user_id: &Uuid) -> Result<Vec<TokenResponse>, AppError> {
+        let tokens = TokenRepo::fetch_all(&self.pool, user_id).await?;
+
+        Ok(tokens.into_iter().map(TokenResponse::from).collect())
+    }
+
+    pub async fn authenticate(&self, token: &str) -> Result<Option<TokenOwner>, AppError> {
+        let owner = TokenRepo::touch(&self.pool, &hash_token(token)).await?;
+
+        Ok(owner)
+    }
+
+    pub async fn revoke(&self, token_id: &Uuid, user_id: &Uuid) -> Result<(), AppError> {
+        TokenRepo::revoke(&self.pool, token_id, user_id).await
+    }
+}
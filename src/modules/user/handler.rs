@@ -1,12 +1,20 @@
 use axum::{Extension, Json, extract::{Path, State}, response::IntoResponse};
 use axum_extra::extract::CookieJar;
 use axum_macros::debug_handler;
-use tower_cookies::Cookie;
-use time::Duration;
+use serde_json::json;
+use uuid::Uuid;
 
 use crate::{
-    common::{error::{AppError, ValidationError}, response::ApiResponse},
-    modules::user::model::{LoginCredentials, LoginDto, SignUpCredentials, SignUpDto, UpdateVisibility, UserId},
+    common::{error::{AppError, AuthError, ValidationError}, response::ApiResponse},
+    middleware::client_info::ClientInfo,
+    modules::{
+        audit::model::AuditEvent,
+        mfa::model::SecondFactorChallenge,
+        privacy::model::Section,
+        rooms::{model::ServerEvent, service::RoomService},
+        session::{handler::{REFRESH_COOKIE, add_auth_cookies, remove_auth_cookies}, model::SessionId},
+        user::model::{ChangePasswordCredentials, ChangePasswordDto, ForgotPasswordDto, LoginCredentials, LoginDto, ProfileChanges, PublicProfile, ResetPasswordCredentials, ResetPasswordDto, SignUpCredentials, SignUpDto, UpdateProfileDto, UserId, UserResponseDto},
+    },
     state::AppState,
     utils::jwt::create_jwt_token,
 };
@@ -14,6 +22,7 @@ use crate::{
 #[debug_handler]
 pub async fn create_user(
     State(state): State<AppState>,
+    client: ClientInfo,
     cookies: CookieJar,
     Json(user): Json<SignUpDto>,
 ) -> Result<impl IntoResponse, AppError> {
@@ -21,11 +30,17 @@ pub async fn create_user(
 
     let user = state.user_service.create(new_user).await?;
 
-    let jwt = create_jwt_token(user.id, user.name.clone(), user.username.clone(), user.email.clone(), state.jwt_encoding)
-        .await
-        .map_err(|_| AppError::Validation(ValidationError::FailedToCreateToken))?;
+    // the account exists either way, a failed email can be retried through resend
+    if let Err(e) = state.verification_service.send_verification(&user.id, &user.email).await {
+        eprintln!("failed to send verification email: {e}");
+    }
 
-    let jar = cookies.add(Cookie::build(("jwt", jwt)).http_only(true).path("/"));
+    let jar = sign_in(&state, &client, cookies, &user).await?;
+
+    state
+        .audit_service
+        .record(Some(user.id), AuditEvent::SignUp, &client, json!({ "username": user.username }))
+        .await?;
 
     Ok((
         jar,
@@ -35,47 +50,219 @@ pub async fn create_user(
 
 pub async fn login_user(
     State(state): State<AppState>,
+    client: ClientInfo,
     cookies: CookieJar,
     Json(dto): Json<LoginDto>,
 ) -> Result<impl IntoResponse, AppError> {
-    let new_user: LoginCredentials = dto.try_into()?;
+    let credentials: LoginCredentials = dto.try_into()?;
+    let email = credentials.email.clone();
+
+    state.lockout_service.check(&email, &client).await?;
+
+    let user = match state.user_service.login(credentials).await {
+        Ok(user) => user,
+        Err(AppError::Unauthorized(AuthError::InvalidCredentials)) => {
+            state.lockout_service.record_failure(&email, &client).await?;
+            return Err(AppError::Unauthorized(AuthError::InvalidCredentials));
+        }
+        Err(e) => return Err(e),
+    };
+
+    let user: UserResponseDto = user.into();
+
+    // with 2fa on, no cookies until the code is checked by second_factor_login_handler
+    if let Some(challenge_token) = state.mfa_service.start_challenge(&user.id).await? {
+        return Ok(Json(ApiResponse::success(
+            "Second factor required",
+            SecondFactorChallenge {
+                second_factor_required: true,
+                challenge_token,
+            },
+        ))
+        .into_response());
+    }
+
+    state.lockout_service.record_success(&email).await?;
+    restore_on_sign_in(&state, &client, &user.id).await?;
+
+    let jar = sign_in(&state, &client, cookies, &user).await?;
+
+    state
+        .audit_service
+        .record(Some(user.id), AuditEvent::LoginSucceeded, &client, json!({ "second_factor": false }))
+        .await?;
+
+    Ok((
+        jar,
+        Json(ApiResponse::success("User login successfuly", user.clone())),
+    )
+        .into_response())
+}
+
+// signing in, once every factor is checked, cancels a pending account deletion
+pub async fn restore_on_sign_in(state: &AppState, client: &ClientInfo, user_id: &Uuid) -> Result<(), AppError> {
+    if state.user_service.restore(user_id).await? {
+        state
+            .audit_service
+            .record(Some(*user_id), AuditEvent::AccountRestored, client, json!({}))
+            .await?;
+    }
+
+    Ok(())
+}
+
+// starts a session and sets the jwt and refresh cookies for it
+pub async fn sign_in(
+    state: &AppState,
+    client: &ClientInfo,
+    cookies: CookieJar,
+    user: &UserResponseDto,
+) -> Result<CookieJar, AppError> {
+    let session = state.session_service.start(&user.id, client).await?;
+
+    let jwt = create_jwt_token(user.id, session.session_id, user.name.clone(), user.username.clone(), user.email.clone(), user.role, state.jwt_encoding.clone())
+        .await
+        .map_err(|_| AppError::Validation(ValidationError::FailedToCreateToken))?;
+
+    Ok(add_auth_cookies(cookies, jwt, session))
+}
+
+pub async fn refresh_handler(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    cookies: CookieJar,
+) -> Result<impl IntoResponse, AppError> {
+    let refresh_token = cookies
+        .get(REFRESH_COOKIE)
+        .map(|c| c.value().to_string())
+        .ok_or(AppError::Unauthorized(AuthError::InvalidRefreshToken))?;
+
+    let session = state.session_service.refresh(&refresh_token, &client).await?;
 
-    let user = state.user_service.login(new_user).await?;
+    let user = state.user_service.get(session.user_id).await?;
 
-    let jwt = create_jwt_token(user.id, user.name.clone(), user.username.clone(), user.email.clone(), state.jwt_encoding)
+    let jwt = create_jwt_token(user.id, session.session_id, user.name.clone(), user.username.clone(), user.email.clone(), user.role, state.jwt_encoding)
         .await
         .map_err(|_| AppError::Validation(ValidationError::FailedToCreateToken))?;
 
-    let jar = cookies.add(Cookie::build(("jwt", jwt)).http_only(true).path("/"));
+    let jar = add_auth_cookies(cookies, jwt, session);
 
     Ok((
         jar,
-        Json(ApiResponse::success("User login successfuly", user.clone())),
+        Json(ApiResponse::success("Session refreshed successfuly", user)),
     ))
 }
 
-pub async fn logout(jar: CookieJar) -> impl IntoResponse {
-    let jar = jar.remove(
-        Cookie::build(("jwt", ""))
-            .http_only(true)
-            .path("/")
-            .max_age(Duration::seconds(0)),
-    );
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    Extension(session_id): Extension<SessionId>,
+    client: ClientInfo,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AppError> {
+    state.session_service.revoke(&session_id.0, &user_id.0).await?;
+
+    state
+        .audit_service
+        .record(Some(user_id.0), AuditEvent::Logout, &client, json!({ "session_id": session_id.0 }))
+        .await?;
+
+    let jar = remove_auth_cookies(jar);
 
-    (
+    Ok((
         jar,
         Json(ApiResponse::success("User logout successfuly", None::<()>)),
-    )
+    ))
 }
 
-pub async fn delete_user_handler(
+pub async fn change_password_handler(
     State(state): State<AppState>,
     Extension(user_id): Extension<UserId>,
+    Extension(session_id): Extension<SessionId>,
+    client: ClientInfo,
+    Json(dto): Json<ChangePasswordDto>,
 ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let credentials: ChangePasswordCredentials = dto.try_into()?;
+
+    // keep the device that changed the password signed in, log out everything else
+    state
+        .user_service
+        .change_password(user_id.0, credentials, Some(session_id.0))
+        .await?;
 
-    state.user_service.delete(user_id.0).await?;
+    state
+        .audit_service
+        .record(Some(user_id.0), AuditEvent::PasswordChanged, &client, json!({}))
+        .await?;
 
-    Ok(Json(ApiResponse::success("User deleted successfuly", None::<()>)))
+    Ok(Json(ApiResponse::success("Password changed successfuly", None::<()>)))
+}
+
+pub async fn forgot_password_handler(
+    State(state): State<AppState>,
+    Json(dto): Json<ForgotPasswordDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    // same answer whether or not the account exists
+    if let Err(e) = state.verification_service.send_password_reset(dto.email.trim()).await {
+        eprintln!("failed to send password reset email: {e}");
+    }
+
+    Ok(Json(ApiResponse::success(
+        "If the account exists a reset link was sent",
+        None::<()>,
+    )))
+}
+
+pub async fn reset_password_handler(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    Json(dto): Json<ResetPasswordDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    let credentials: ResetPasswordCredentials = dto.try_into()?;
+
+    let user_id = state.verification_service.reset_password(credentials).await?;
+
+    state
+        .audit_service
+        .record(Some(user_id), AuditEvent::PasswordReset, &client, json!({}))
+        .await?;
+
+    Ok(Json(ApiResponse::success("Password reset successfuly", None::<()>)))
+}
+
+pub async fn delete_user_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+    client: ClientInfo,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, AppError> {
+    // the account is only hidden here, jobs::account_purge removes it after the grace period
+    state.user_service.schedule_deletion(user_id.0).await?;
+    state.session_service.revoke_all_for_user(&user_id.0, None).await?;
+
+    RoomService::disconnect_user(&state, &user_id.0, ServerEvent::AccountDeleted).await;
+
+    let grace_days = state.user_service.deletion_grace_days();
+
+    state
+        .audit_service
+        .record(
+            Some(user_id.0),
+            AuditEvent::AccountDeletionScheduled,
+            &client,
+            json!({ "grace_days": grace_days }),
+        )
+        .await?;
+
+    let jar = remove_auth_cookies(jar);
+
+    Ok((
+        jar,
+        Json(ApiResponse::success(
+            format!("Account scheduled for deletion, sign in within {grace_days} days to restore it"),
+            None::<()>,
+        )),
+    ))
 }
 
 pub async fn get_user_handler(
@@ -94,26 +281,70 @@ pub async fn get_user_by_username_handler(
 ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
     let user = state.user_service.get_user_by_username(&username).await?; 
 
-    if !user.is_public {
-        let requester_id = user_id
-            .map(|Extension(user_id)| user_id.0)
-            .ok_or(AppError::Validation(ValidationError::UnauthorizedAccess))?;
+    let audience = state
+        .privacy_service
+        .audience(user_id.map(|Extension(user_id)| user_id.0), &user.id)
+        .await?;
 
-        if user.id != requester_id {
-            return Err(AppError::Validation(ValidationError::UnauthorizedAccess));
-        } 
-    }
+    audience.ensure(Section::Profile)?;
 
-    Ok(Json(ApiResponse::success("fetch user successfuly", user)))
+    // the counts belong to the followers section
+    let counts = if audience.can_view(Section::Followers) {
+        Some(state.follow_service.counts(&user.id).await?)
+    } else {
+        None
+    };
+
+    Ok(Json(ApiResponse::success(
+        "fetch user successfuly",
+        PublicProfile {
+            user,
+            followers_count: counts.map(|counts| counts.followers),
+            following_count: counts.map(|counts| counts.following),
+        },
+    )))
 }
 
-#[debug_handler]
-pub async fn change_user_visibility_handler(
+pub async fn update_profile_handler(
     State(state): State<AppState>,
     Extension(user_id): Extension<UserId>,
-    Json(visibility): Json<UpdateVisibility>
+    session_id: Option<Extension<SessionId>>,
+    client: ClientInfo,
+    Json(dto): Json<UpdateProfileDto>,
 ) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
-    state.user_service.change_visibility(user_id.0, visibility.is_public).await?;
+    let changes: ProfileChanges = dto.try_into()?;
+
+    // a new password logs out every session but this one
+    let current = session_id.map(|Extension(session_id)| session_id.0);
+    let outcome = state.user_service.update_profile(user_id.0, changes, current).await?;
+
+    if let Some(previous_email) = &outcome.previous_email {
+        state
+            .audit_service
+            .record(
+                Some(user_id.0),
+                AuditEvent::EmailChanged,
+                &client,
+                json!({ "from": previous_email, "to": outcome.user.email }),
+            )
+            .await?;
+    }
+
+    if outcome.password_changed {
+        state
+            .audit_service
+            .record(Some(user_id.0), AuditEvent::PasswordChanged, &client, json!({}))
+            .await?;
+    }
+
+    if outcome.previous_email.is_some()
+        && let Err(e) = state
+            .verification_service
+            .send_verification(&outcome.user.id, &outcome.user.email)
+            .await
+    {
+        eprintln!("failed to send verification email: {e}");
+    }
 
-    Ok(Json(ApiResponse::success("User account visibility changed successfully", None::<()>)))
+    Ok(Json(ApiResponse::success("Profile updated successfuly", outcome.user)))
 }
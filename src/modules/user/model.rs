@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
+use time::OffsetDateTime;
 use uuid::Uuid;
 
-use crate::{common::error::ValidationError};
+use crate::common::error::{ForbiddenError, ValidationError};
 
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct User {
@@ -10,8 +11,19 @@ pub struct User {
     pub name: String,
     pub username: String,
     pub email: String,
+    #[serde(skip_serializing)]
     pub password: String,
-    pub is_public: bool
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub links: Vec<String>,
+    pub role: Role,
+    #[serde(skip_serializing)]
+    pub suspended_until: Option<OffsetDateTime>,
+    #[serde(skip_serializing)]
+    pub suspension_reason: Option<String>,
+    #[serde(skip_serializing)]
+    pub deleted_at: Option<OffsetDateTime>,
+    pub email_verified: bool
 }
 
 #[derive(Serialize, Clone)]
@@ -20,12 +32,77 @@ pub struct UserResponseDto {
     pub name: String,
     pub username: String,
     pub email: String,
-    pub is_public: bool
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub links: Vec<String>,
+    pub role: Role,
+    pub email_verified: bool
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct UpdateVisibility {
-    pub is_public: bool
+impl From<User> for UserResponseDto {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            name: user.name,
+            username: user.username,
+            email: user.email,
+            bio: user.bio,
+            avatar_url: user.avatar_url,
+            links: user.links,
+            role: user.role,
+            email_verified: user.email_verified,
+        }
+    }
+}
+
+// what get_user_by_username_handler returns
+#[derive(Serialize)]
+pub struct PublicProfile {
+    #[serde(flatten)]
+    pub user: User,
+    // left out when the viewer can't see the followers section
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub followers_count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub following_count: Option<i64>,
+}
+
+// platform wide, ordered so a higher role includes everything below it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    User,
+    Moderator,
+    Admin,
+}
+
+#[derive(Debug, Clone)]
+pub struct Suspension {
+    pub reason: String,
+    pub until: OffsetDateTime,
+}
+
+impl Suspension {
+    // a suspension whose end date has passed no longer counts
+    pub fn active(until: Option<OffsetDateTime>, reason: Option<String>) -> Option<Self> {
+        until
+            .filter(|until| *until > OffsetDateTime::now_utc())
+            .map(|until| Self {
+                reason: reason.unwrap_or_default(),
+                until,
+            })
+    }
+}
+
+impl From<Suspension> for ForbiddenError {
+    fn from(value: Suspension) -> Self {
+        ForbiddenError::AccountSuspended {
+            until: value.until.date(),
+            reason: value.reason,
+        }
+    }
 }
 
 pub struct SignUpCredentials {
@@ -35,11 +112,62 @@ pub struct SignUpCredentials {
     pub password: String,
 }
 
+// every field is optional, an empty bio or avatar_url clears it
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileDto {
+    pub name: Option<String>,
+    pub username: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub links: Option<Vec<String>>,
+    pub email: Option<String>,
+    pub new_password: Option<String>,
+    pub current_password: Option<String>,
+}
+
+pub struct ProfileChanges {
+    pub name: Option<String>,
+    pub username: Option<String>,
+    pub bio: Option<Option<String>>,
+    pub avatar_url: Option<Option<String>>,
+    pub links: Option<Vec<String>>,
+    pub email: Option<String>,
+    pub new_password: Option<String>,
+    pub current_password: Option<String>,
+}
+
+// the full profile after applying `ProfileChanges` to the stored row
+pub struct ProfileUpdate {
+    pub name: String,
+    pub username: String,
+    pub email: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub links: Vec<String>,
+}
+
+pub struct ProfileUpdateOutcome {
+    pub user: UserResponseDto,
+    // set only when the email actually changed
+    pub previous_email: Option<String>,
+    pub password_changed: bool,
+}
+
+pub struct ResetPasswordCredentials {
+    pub token: String,
+    pub new_password: String,
+}
+
 pub struct LoginCredentials {
     pub email: String,
     pub password: String
 }
 
+pub struct ChangePasswordCredentials {
+    pub current_password: String,
+    pub new_password: String,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct UserId(pub Uuid);
 
@@ -49,6 +177,23 @@ pub struct LoginDto {
     pub email: String,
     pub password: String,
 }
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChangePasswordDto {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordDto {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordDto {
+    pub token: String,
+    pub new_password: String,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SignUpDto {
     pub name: String,
@@ -65,7 +210,7 @@ impl TryFrom<SignUpDto> for SignUpCredentials {
         let password = value.password.trim();
         let username = value.username.trim();
 
-        if !email.contains("@") || email.len() < 5 {
+        if !is_valid_email(email) {
             return Err(ValidationError::InvalidEmail);
         };
 
@@ -73,6 +218,8 @@ impl TryFrom<SignUpDto> for SignUpCredentials {
             return Err(ValidationError::TooShortName);
         };
 
+        validate_username(username)?;
+
         if password.len() < 5 {
             return Err(ValidationError::InvalidPassword)
         };
@@ -93,7 +240,7 @@ impl TryFrom<LoginDto> for LoginCredentials {
         let email = value.email.trim();
         let password = value.password.trim();
 
-        if !email.contains("@") || email.len() < 5 {
+        if !is_valid_email(email) {
             return Err(ValidationError::InvalidEmail);
         };
         if password.len() < 6 {
@@ -105,4 +252,167 @@ impl TryFrom<LoginDto> for LoginCredentials {
             password: password.to_string()
         })
     }
-}
\ No newline at end of file
+}
+
+impl TryFrom<ChangePasswordDto> for ChangePasswordCredentials {
+    type Error = ValidationError;
+    fn try_from(value: ChangePasswordDto) -> Result<Self, Self::Error> {
+        let current_password = value.current_password.trim();
+        let new_password = value.new_password.trim();
+
+        if new_password.len() < 6 {
+            return Err(ValidationError::InvalidPassword);
+        };
+
+        Ok(Self {
+            current_password: current_password.to_string(),
+            new_password: new_password.to_string()
+        })
+    }
+}
+
+impl TryFrom<ResetPasswordDto> for ResetPasswordCredentials {
+    type Error = ValidationError;
+    fn try_from(value: ResetPasswordDto) -> Result<Self, Self::Error> {
+        let token = value.token.trim();
+        let new_password = value.new_password.trim();
+
+        if token.is_empty() {
+            return Err(ValidationError::InvalidOrExpiredToken);
+        };
+        if new_password.len() < 6 {
+            return Err(ValidationError::InvalidPassword);
+        };
+
+        Ok(Self {
+            token: token.to_string(),
+            new_password: new_password.to_string()
+        })
+    }
+}
+
+const MAX_BIO_LENGTH: usize = 280;
+const MIN_USERNAME_LENGTH: usize = 3;
+const MAX_USERNAME_LENGTH: usize = 30;
+
+// names that collide with routes or could be mistaken for staff accounts
+const RESERVED_USERNAMES: &[&str] = &[
+    "about", "admin", "administrator", "api", "auth", "create", "delete", "help", "login",
+    "logout", "me", "mod", "moderator", "null", "password", "refresh", "root", "security",
+    "sessions", "settings", "signup", "staff", "support", "system", "tokens", "undefined",
+    "user", "users", "verify_email", "wiki",
+];
+
+// starts with a letter, then letters, digits or single underscores, compared case-insensitively
+pub fn validate_username(username: &str) -> Result<(), ValidationError> {
+    let valid = (MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&username.len())
+        && username.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !username.ends_with('_')
+        && !username.contains("__")
+        && username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !valid {
+        return Err(ValidationError::InvalidUsername);
+    }
+
+    if RESERVED_USERNAMES.contains(&username.to_ascii_lowercase().as_str()) {
+        return Err(ValidationError::ReservedUsername);
+    }
+
+    Ok(())
+}
+const MAX_LINKS: usize = 5;
+const MAX_URL_LENGTH: usize = 2048;
+
+impl TryFrom<UpdateProfileDto> for ProfileChanges {
+    type Error = ValidationError;
+    fn try_from(value: UpdateProfileDto) -> Result<Self, Self::Error> {
+        let name = value.name.map(|name| name.trim().to_string());
+        if let Some(name) = &name
+            && name.len() < 3
+        {
+            return Err(ValidationError::TooShortName);
+        };
+
+        let username = value.username.map(|username| username.trim().to_string());
+        if let Some(username) = &username {
+            validate_username(username)?;
+        };
+
+        let bio = value.bio.map(|bio| Some(bio.trim().to_string()).filter(|bio| !bio.is_empty()));
+        if let Some(Some(bio)) = &bio
+            && bio.chars().count() > MAX_BIO_LENGTH
+        {
+            return Err(ValidationError::BioTooLong);
+        };
+
+        let avatar_url = value
+            .avatar_url
+            .map(|url| Some(url.trim().to_string()).filter(|url| !url.is_empty()));
+        if let Some(Some(url)) = &avatar_url
+            && !is_valid_url(url)
+        {
+            return Err(ValidationError::InvalidProfilePicUrl);
+        };
+
+        let links = value.links.map(|links| {
+            links
+                .iter()
+                .map(|link| link.trim().to_string())
+                .filter(|link| !link.is_empty())
+                .collect::<Vec<_>>()
+        });
+        if let Some(links) = &links
+            && (links.len() > MAX_LINKS || !links.iter().all(|link| is_valid_url(link)))
+        {
+            return Err(ValidationError::InvalidLinks);
+        };
+
+        let email = value.email.map(|email| email.trim().to_string());
+        if let Some(email) = &email
+            && !is_valid_email(email)
+        {
+            return Err(ValidationError::InvalidEmail);
+        };
+
+        let new_password = value.new_password.map(|password| password.trim().to_string());
+        if let Some(password) = &new_password
+            && password.len() < 6
+        {
+            return Err(ValidationError::InvalidPassword);
+        };
+
+        Ok(Self {
+            name,
+            username,
+            bio,
+            avatar_url,
+            links,
+            email,
+            new_password,
+            current_password: value.current_password,
+        })
+    }
+}
+
+pub fn is_valid_url(url: &str) -> bool {
+    url.len() <= MAX_URL_LENGTH
+        && !url.chars().any(char::is_whitespace)
+        && ["https://", "http://"]
+            .iter()
+            .any(|scheme| url.strip_prefix(scheme).is_some_and(|rest| !rest.is_empty()))
+}
+
+// one "@", a non-empty local part and a dotted domain, no whitespace
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && email.len() <= 254
+        && !domain.contains('@')
+        && !email.chars().any(char::is_whitespace)
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|part| !part.is_empty())
+}
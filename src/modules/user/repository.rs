@@ -1,9 +1,9 @@
-use sqlx::{PgPool, Result};
+use sqlx::{PgPool, Postgres, Result, Transaction};
 use uuid::Uuid;
 
 use crate::{
-    common::error::AppError,
-    modules::{rooms::{model::Members, service::Username}, user::model::{User, UserResponseDto}},
+    common::error::{AppError, NotFoundError},
+    modules::{rooms::{model::Members, service::Username}, user::model::{ProfileUpdate, Role, User, UserResponseDto}},
 };
 pub struct UserRepo;
 
@@ -20,7 +20,7 @@ impl UserRepo {
             r#"
         INSERT INTO users (name, username, email, password)
         VALUES ($1, $2, $3, $4)
-        RETURNING id, name, username, email, is_public
+        RETURNING id, name, username, email, bio, avatar_url, links, role AS "role: Role", email_verified_at IS NOT NULL AS "email_verified!"
         "#,
             name,
             username,
@@ -37,7 +37,7 @@ impl UserRepo {
         let user = sqlx::query_as!(
             UserResponseDto,
             r#"
-        SELECT id, name, username, email, is_public
+        SELECT id, name, username, email, bio, avatar_url, links, role AS "role: Role", email_verified_at IS NOT NULL AS "email_verified!"
         FROM users
         WHERE id = $1
         "#,
@@ -49,7 +49,58 @@ impl UserRepo {
         Ok(user)
     }
 
-    pub async fn delete(pool: &PgPool, user_id: Uuid) -> Result<(), AppError> {
+    pub async fn fetch_by_id_with_password(pool: &PgPool, user_id: Uuid) -> Result<Option<User>> {
+        let user = sqlx::query_as!(
+            User,
+            r#"
+        SELECT id, name, username, email, password, bio, avatar_url, links, role AS "role: Role", suspended_until, suspension_reason, deleted_at,
+            email_verified_at IS NOT NULL AS "email_verified!"
+        FROM users
+        WHERE id = $1
+        "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    // owned rooms pass to their longest standing member, rooms nobody else joined are closed
+    // todos, progress, tags and categories go with the user, messages stay without an author
+    // returns the ids of the closed rooms
+    pub async fn delete(pool: &PgPool, user_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE rooms r
+            SET owner_id = heir.user_id
+            FROM (
+                SELECT DISTINCT ON (m.room_id) m.room_id, m.user_id
+                FROM members m
+                JOIN rooms owned ON owned.id = m.room_id
+                WHERE owned.owner_id = $1 AND m.user_id <> $1
+                ORDER BY m.room_id, m.joined_at, m.user_id
+            ) heir
+            WHERE r.id = heir.room_id
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let closed_rooms = sqlx::query_scalar!(
+            r#"
+            DELETE FROM rooms
+            WHERE owner_id = $1
+            RETURNING id
+            "#,
+            user_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
         let result = sqlx::query!(
             "
         DELETE FROM users
@@ -57,21 +108,75 @@ impl UserRepo {
         ",
             user_id
         )
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
         if result.rows_affected() == 0 {
             return Err(AppError::Failed("Failed to delete user".into()));
         }
 
+        tx.commit().await?;
+
+        Ok(closed_rooms)
+    }
+
+    // hides the account until it is restored or purged
+    pub async fn schedule_deletion(pool: &PgPool, user_id: &Uuid) -> Result<(), AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET deleted_at = now()
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(NotFoundError::UserNotFound));
+        }
+
         Ok(())
     }
 
+    pub async fn restore(pool: &PgPool, user_id: &Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET deleted_at = NULL
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            "#,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    // accounts whose grace period is over
+    pub async fn fetch_expired_deletions(pool: &PgPool, grace_days: i32) -> Result<Vec<Uuid>> {
+        let user_ids = sqlx::query_scalar!(
+            r#"
+            SELECT id
+            FROM users
+            WHERE deleted_at < now() - make_interval(days => $1)
+            "#,
+            grace_days
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(user_ids)
+    }
+
     pub async fn fetch_by_email(pool: &PgPool, email: &str) -> Result<Option<User>> {
         let user = sqlx::query_as!(
             User,
             r#"
-        SELECT id, name, username, email, password, is_public
+        SELECT id, name, username, email, password, bio, avatar_url, links, role AS "role: Role", suspended_until, suspension_reason, deleted_at,
+            email_verified_at IS NOT NULL AS "email_verified!"
         FROM users
         WHERE email = $1
         "#,
@@ -87,9 +192,10 @@ impl UserRepo {
         let user = sqlx::query_as!(
             User,
             r#"
-        SELECT id, name, username, email, password, is_public
+        SELECT id, name, username, email, password, bio, avatar_url, links, role AS "role: Role", suspended_until, suspension_reason, deleted_at,
+            email_verified_at IS NOT NULL AS "email_verified!"
         FROM users
-        WHERE username = $1
+        WHERE lower(username) = lower($1)
         "#,
             username
         )
@@ -113,27 +219,219 @@ impl UserRepo {
         Ok(users)
     }
 
-    pub async fn change_visibility(
+    // a name counts as taken while another account holds it or recently renamed away from it
+    // emails are unique regardless of case, `user_id` is left out so an account keeps its own
+    pub async fn is_email_available(pool: &PgPool, email: &str, user_id: Option<Uuid>) -> Result<bool> {
+        let taken = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM users
+                WHERE lower(email) = lower($1) AND id IS DISTINCT FROM $2
+            ) AS "taken!"
+            "#,
+            email,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(!taken)
+    }
+
+    pub async fn is_username_available(
+        pool: &PgPool,
+        username: &str,
+        user_id: Option<Uuid>,
+        grace_days: i32,
+    ) -> Result<bool> {
+        let taken = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM users
+                WHERE lower(username) = lower($1) AND id IS DISTINCT FROM $2
+            ) OR EXISTS (
+                SELECT 1 FROM username_history
+                WHERE lower(username) = lower($1)
+                    AND user_id IS DISTINCT FROM $2
+                    AND changed_at > now() - make_interval(days => $3)
+            ) AS "taken!"
+            "#,
+            username,
+            user_id,
+            grace_days
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(!taken)
+    }
+
+    pub async fn fetch_renamed_user_id(
+        pool: &PgPool,
+        old_username: &str,
+        grace_days: i32,
+    ) -> Result<Option<Uuid>> {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            SELECT user_id
+            FROM username_history
+            WHERE lower(username) = lower($1)
+                AND changed_at > now() - make_interval(days => $2)
+            ORDER BY changed_at DESC
+            LIMIT 1
+            "#,
+            old_username,
+            grace_days
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user_id)
+    }
+
+    // writes the already merged profile, a changed email has to be verified again
+    pub async fn update_profile(
+        pool: &PgPool,
+        user_id: &Uuid,
+        profile: &ProfileUpdate,
+        password_hash: Option<&str>,
+        keep_session: Option<Uuid>,
+    ) -> Result<UserResponseDto> {
+        let mut tx = pool.begin().await?;
+
+        // remember the old name so links to it keep resolving, case-only changes don't count
+        sqlx::query!(
+            r#"
+            INSERT INTO username_history (user_id, username)
+            SELECT id, username
+            FROM users
+            WHERE id = $1 AND lower(username) <> lower($2)
+            "#,
+            user_id,
+            profile.username
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let user = sqlx::query_as!(
+            UserResponseDto,
+            r#"
+            UPDATE users
+            SET name = $1,
+                username = $2,
+                bio = $3,
+                avatar_url = $4,
+                links = $5,
+                email_verified_at = CASE WHEN email = $6 THEN email_verified_at ELSE NULL END,
+                email = $6,
+                password = COALESCE($7, password)
+            WHERE id = $8
+            RETURNING id, name, username, email, bio, avatar_url, links, role AS "role: Role", email_verified_at IS NOT NULL AS "email_verified!"
+            "#,
+            profile.name,
+            profile.username,
+            profile.bio,
+            profile.avatar_url,
+            &profile.links,
+            profile.email,
+            password_hash,
+            user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if password_hash.is_some() {
+            Self::revoke_credentials(&mut tx, user_id, keep_session).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(user)
+    }
+
+    // a new password signs out every other session and revokes every token in the same transaction
+    pub async fn change_password(
+        pool: &PgPool,
+        user_id: &Uuid,
+        password_hash: &str,
+        keep_session: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET password = $1
+            WHERE id = $2
+            "#,
+            password_hash,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Failed("Failed to update user's password".into()));
+        }
+
+        Self::revoke_credentials(&mut tx, user_id, keep_session).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // the old password may have leaked, so may anything created with it
+    async fn revoke_credentials(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &Uuid,
+        keep_session: Option<Uuid>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE sessions
+            SET revoked_at = now()
+            WHERE user_id = $1 AND revoked_at IS NULL AND id IS DISTINCT FROM $2
+            "#,
+            user_id,
+            keep_session
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE personal_access_tokens
+            SET revoked_at = now()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_password(
         pool: &PgPool,
         user_id: &Uuid,
-        is_public: bool,
+        password_hash: &str,
     ) -> Result<(), AppError> {
         let result = sqlx::query!(
             r#"
             UPDATE users
-            SET is_public = $1
+            SET password = $1
             WHERE id = $2
             "#,
-            is_public,
+            password_hash,
             user_id
         )
         .execute(pool)
         .await?;
 
         if result.rows_affected() == 0 {
-            return Err(AppError::Failed(
-                "Failed to update user's visibility status".into(),
-            ));
+            return Err(AppError::Failed("Failed to update user's password".into()));
         }
 
         Ok(())
@@ -1,37 +1,59 @@
 use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use tokio::sync::OnceCell;
 use uuid::Uuid;
 
 use crate::{
-    common::error::{AppError, NotFoundError, ValidationError},
+    common::error::{AppError, AuthError, NotFoundError, ValidationError},
     modules::user::{
-        model::{LoginCredentials, SignUpCredentials, User, UserResponseDto},
+        model::{
+            ChangePasswordCredentials, LoginCredentials, ProfileChanges, ProfileUpdate,
+            ProfileUpdateOutcome, SignUpCredentials, Suspension, User, UserResponseDto,
+        },
         repository::UserRepo,
     },
+    utils::password::{PasswordCheck, PasswordConfig, hash_password, verify_password},
 };
 
+static DUMMY_HASH: OnceCell<String> = OnceCell::const_new();
+
+// how long an old username keeps pointing at the renamed account
+const USERNAME_REDIRECT_DAYS: i32 = 30;
+
 #[derive(Debug, Clone)]
 pub struct UserService {
     pool: PgPool,
+    password_config: PasswordConfig,
+    // how long a deleted account can still be restored by signing in
+    deletion_grace_days: i32,
 }
 
 impl UserService {
     
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, password_config: PasswordConfig, deletion_grace_days: i32) -> Self {
+        Self { pool, password_config, deletion_grace_days }
     }
 
-    pub async fn create(&self, user: SignUpCredentials) -> Result<UserResponseDto, AppError> {
-        let option_user = UserRepo::fetch_by_email(&self.pool, &user.email).await?;
+    pub fn deletion_grace_days(&self) -> i32 {
+        self.deletion_grace_days
+    }
 
-        if option_user.is_some() {
+    pub async fn create(&self, user: SignUpCredentials) -> Result<UserResponseDto, AppError> {
+        if !UserRepo::is_email_available(&self.pool, &user.email, None).await? {
             return Err(AppError::Validation(ValidationError::UserAlreadyExits));
         }
 
+        if !UserRepo::is_username_available(&self.pool, &user.username, None, USERNAME_REDIRECT_DAYS).await? {
+            return Err(AppError::Validation(ValidationError::UsernameTaken));
+        }
+
+        let password_hash = hash_password(&user.password, &self.password_config).await?;
+
         let created_user = UserRepo::create(
             &self.pool,
             &user.name,
             &user.email,
-            &user.password,
+            &password_hash,
             &user.username,
         )
         .await?;
@@ -40,21 +62,163 @@ impl UserService {
     }
 
     pub async fn login(&self, user: LoginCredentials) -> Result<User, AppError> {
-        let db_user = UserRepo::fetch_by_email(&self.pool, &user.email)
+        let Some(db_user) = UserRepo::fetch_by_email(&self.pool, &user.email).await? else {
+            // burn the same hashing time as a real check so response timing doesn't reveal the account
+            let dummy = DUMMY_HASH
+                .get_or_try_init(|| hash_password("not-a-real-password", &self.password_config))
+                .await?;
+            verify_password(&user.password, dummy, &self.password_config).await?;
+
+            return Err(AppError::Unauthorized(AuthError::InvalidCredentials));
+        };
+
+        match verify_password(&user.password, &db_user.password, &self.password_config).await? {
+            PasswordCheck::Invalid => {
+                return Err(AppError::Unauthorized(AuthError::InvalidCredentials));
+            }
+            PasswordCheck::ValidNeedsRehash => {
+                let password_hash = hash_password(&user.password, &self.password_config).await?;
+                UserRepo::update_password(&self.pool, &db_user.id, &password_hash).await?;
+            }
+            PasswordCheck::Valid => {}
+        }
+
+        // only checked after the password so the suspension isn't revealed to strangers
+        if let Some(suspension) =
+            Suspension::active(db_user.suspended_until, db_user.suspension_reason.clone())
+        {
+            return Err(AppError::Forbidden(suspension.into()));
+        }
+
+        // past the grace period the account is only waiting for the purge job
+        if let Some(deleted_at) = db_user.deleted_at
+            && deleted_at + Duration::days(self.deletion_grace_days.into()) <= OffsetDateTime::now_utc()
+        {
+            return Err(AppError::Unauthorized(AuthError::InvalidCredentials));
+        }
+
+        Ok(db_user)
+    }
+
+    // cancels a pending deletion, true when there was one
+    pub async fn restore(&self, user_id: &Uuid) -> Result<bool, AppError> {
+        UserRepo::restore(&self.pool, user_id).await
+    }
+
+    pub async fn change_password(
+        &self,
+        user_id: Uuid,
+        credentials: ChangePasswordCredentials,
+        keep_session: Option<Uuid>,
+    ) -> Result<(), AppError> {
+        let user = UserRepo::fetch_by_id_with_password(&self.pool, user_id)
             .await?
             .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
 
-        if user.password != db_user.password {
+        let check = verify_password(
+            &credentials.current_password,
+            &user.password,
+            &self.password_config,
+        )
+        .await?;
+
+        if check == PasswordCheck::Invalid {
             return Err(AppError::Validation(ValidationError::InvalidPassword));
         }
 
-        Ok(db_user)
+        let password_hash = hash_password(&credentials.new_password, &self.password_config).await?;
+        UserRepo::change_password(&self.pool, &user.id, &password_hash, keep_session).await?;
+
+        Ok(())
     }
 
-    pub async fn delete(&self, user_id: Uuid) -> Result<(), AppError> {
-        UserRepo::delete(&self.pool, user_id).await?;
+    pub async fn update_profile(
+        &self,
+        user_id: Uuid,
+        changes: ProfileChanges,
+        keep_session: Option<Uuid>,
+    ) -> Result<ProfileUpdateOutcome, AppError> {
+        let user = UserRepo::fetch_by_id_with_password(&self.pool, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        if let Some(username) = &changes.username
+            && !UserRepo::is_username_available(&self.pool, username, Some(user_id), USERNAME_REDIRECT_DAYS).await?
+        {
+            return Err(AppError::Validation(ValidationError::UsernameTaken));
+        }
+
+        let email_changed = changes.email.as_ref().is_some_and(|email| *email != user.email);
 
-        Ok(())
+        if email_changed
+            && let Some(email) = &changes.email
+            && !UserRepo::is_email_available(&self.pool, email, Some(user_id)).await?
+        {
+            return Err(AppError::Validation(ValidationError::UserAlreadyExits));
+        }
+        let password_changed = changes.new_password.is_some();
+
+        // changing how the account signs in needs the current password
+        if email_changed || password_changed {
+            let current_password = changes
+                .current_password
+                .as_deref()
+                .ok_or(AppError::Validation(ValidationError::CurrentPasswordRequired))?;
+
+            let check = verify_password(current_password, &user.password, &self.password_config).await?;
+
+            if check == PasswordCheck::Invalid {
+                return Err(AppError::Validation(ValidationError::InvalidPassword));
+            }
+        }
+
+        let password_hash = match &changes.new_password {
+            Some(password) => Some(hash_password(password, &self.password_config).await?),
+            None => None,
+        };
+
+        let previous_email = email_changed.then(|| user.email.clone());
+
+        let profile = ProfileUpdate {
+            name: changes.name.unwrap_or(user.name),
+            username: changes.username.unwrap_or(user.username),
+            email: changes.email.unwrap_or(user.email),
+            bio: changes.bio.unwrap_or(user.bio),
+            avatar_url: changes.avatar_url.unwrap_or(user.avatar_url),
+            links: changes.links.unwrap_or(user.links),
+        };
+
+        let user = UserRepo::update_profile(
+            &self.pool,
+            &user_id,
+            &profile,
+            password_hash.as_deref(),
+            keep_session,
+        )
+        .await?;
+
+        Ok(ProfileUpdateOutcome {
+            user,
+            previous_email,
+            password_changed,
+        })
+    }
+
+    pub async fn schedule_deletion(&self, user_id: Uuid) -> Result<(), AppError> {
+        UserRepo::schedule_deletion(&self.pool, &user_id).await
+    }
+
+    pub async fn expired_deletions(&self) -> Result<Vec<Uuid>, AppError> {
+        let user_ids = UserRepo::fetch_expired_deletions(&self.pool, self.deletion_grace_days).await?;
+
+        Ok(user_ids)
+    }
+
+    // returns the rooms that were closed because nobody was left to take them over
+    pub async fn delete(&self, user_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let closed_rooms = UserRepo::delete(&self.pool, user_id).await?;
+
+        Ok(closed_rooms)
     }
 
     pub async fn get(&self, user_id: Uuid) -> Result<UserResponseDto, AppError> {
@@ -65,16 +229,29 @@ impl UserService {
         Ok(user)
     }
 
-    pub async fn change_visibility(&self, user_id: Uuid, is_public: bool) -> Result<(), AppError> {
-        UserRepo::change_visibility(&self.pool, &user_id, is_public).await?;
-        Ok(())
-    }
-
+    // accounts scheduled for deletion are hidden like they were already gone
     pub async fn get_user_by_username(&self, username: &str) -> Result<User, AppError> {
-        let user = UserRepo::fetch_by_username(&self.pool, username)
+        if let Some(user) = UserRepo::fetch_by_username(&self.pool, username).await? {
+            return Self::visible(user);
+        }
+
+        // fall back to a recent rename so old profile links keep working
+        let user_id = UserRepo::fetch_renamed_user_id(&self.pool, username, USERNAME_REDIRECT_DAYS)
+            .await?
+            .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        let user = UserRepo::fetch_by_id_with_password(&self.pool, user_id)
             .await?
             .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
 
+        Self::visible(user)
+    }
+
+    fn visible(user: User) -> Result<User, AppError> {
+        if user.deleted_at.is_some() {
+            return Err(AppError::NotFound(NotFoundError::UserNotFound));
+        }
+
         Ok(user)
     }
 }
@@ -0,0 +1,25 @@
+use axum::{Extension, Json, extract::State};
+
+use crate::{
+    common::{error::AppError, response::ApiResponse},
+    modules::{user::model::UserId, verification::model::VerifyEmailDto},
+    state::AppState,
+};
+
+pub async fn verify_email_handler(
+    State(state): State<AppState>,
+    Json(dto): Json<VerifyEmailDto>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    state.verification_service.verify(dto.token.trim()).await?;
+
+    Ok(Json(ApiResponse::success("Email verified successfuly", None::<()>)))
+}
+
+pub async fn resend_verification_handler(
+    State(state): State<AppState>,
+    Extension(user_id): Extension<UserId>,
+) -> Result<Json<ApiResponse<impl serde::Serialize>>, AppError> {
+    state.verification_service.resend(&user_id.0).await?;
+
+    Ok(Json(ApiResponse::success("Verification email sent", None::<()>)))
+}
@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::{common::error::ValidationError, utils::config::Config};
+
+pub enum EmailTokenPurpose {
+    VerifyEmail,
+    PasswordReset,
+}
+
+impl EmailTokenPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailTokenPurpose::VerifyEmail => "verify_email",
+            EmailTokenPurpose::PasswordReset => "password_reset",
+        }
+    }
+}
+
+// things an account can be barred from until its email is confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestrictedAction {
+    CreateRoom,
+    JoinRoom,
+    CreateToken,
+}
+
+impl FromStr for RestrictedAction {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create_room" => Ok(RestrictedAction::CreateRoom),
+            "join_room" => Ok(RestrictedAction::JoinRoom),
+            "create_token" => Ok(RestrictedAction::CreateToken),
+            _ => Err(ValidationError::InvalidRestrictedAction),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VerificationPolicy {
+    pub restricted: Vec<RestrictedAction>,
+}
+
+impl VerificationPolicy {
+    // UNVERIFIED_RESTRICTIONS is a comma separated list, e.g. "create_room,join_room"
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let value = Config::UnverifiedRestrictions.parse_or("create_room".to_string())?;
+
+        let restricted = value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(RestrictedAction::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { restricted })
+    }
+
+    pub fn restricts(&self, action: RestrictedAction) -> bool {
+        self.restricted.contains(&action)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailDto {
+    pub token: String,
+}
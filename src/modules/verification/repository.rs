@@ -0,0 +1,176 @@
+use sqlx::{PgPool, Result};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::modules::verification::model::EmailTokenPurpose;
+
+pub struct EmailTokenRepo;
+
+impl EmailTokenRepo {
+    // issuing a new token invalidates any earlier one for the same purpose
+    pub async fn create(
+        pool: &PgPool,
+        user_id: &Uuid,
+        purpose: EmailTokenPurpose,
+        token_hash: &str,
+        expires_at: OffsetDateTime,
+    ) -> Result<()> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE email_tokens
+            SET used_at = now()
+            WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL
+            "#,
+            user_id,
+            purpose.as_str()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO email_tokens (user_id, purpose, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            user_id,
+            purpose.as_str(),
+            token_hash,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn last_issued_at(
+        pool: &PgPool,
+        user_id: &Uuid,
+        purpose: EmailTokenPurpose,
+    ) -> Result<Option<OffsetDateTime>> {
+        let created_at = sqlx::query_scalar!(
+            r#"
+            SELECT max(created_at)
+            FROM email_tokens
+            WHERE user_id = $1 AND purpose = $2
+            "#,
+            user_id,
+            purpose.as_str()
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(created_at)
+    }
+
+    pub async fn verify_email(pool: &PgPool, token_hash: &str) -> Result<Option<Uuid>> {
+        let mut tx = pool.begin().await?;
+
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE email_tokens
+            SET used_at = now()
+            WHERE token_hash = $1
+                AND purpose = $2
+                AND used_at IS NULL
+                AND expires_at > now()
+            RETURNING user_id
+            "#,
+            token_hash,
+            EmailTokenPurpose::VerifyEmail.as_str()
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(user_id) = user_id else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET email_verified_at = now()
+            WHERE id = $1 AND email_verified_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(user_id))
+    }
+
+    // consumes the token, swaps the password and signs out every session in one go
+    pub async fn reset_password(
+        pool: &PgPool,
+        token_hash: &str,
+        password_hash: &str,
+    ) -> Result<Option<Uuid>> {
+        let mut tx = pool.begin().await?;
+
+        let user_id = sqlx::query_scalar!(
+            r#"
+            UPDATE email_tokens
+            SET used_at = now()
+            WHERE token_hash = $1
+                AND purpose = $2
+                AND used_at IS NULL
+                AND expires_at > now()
+            RETURNING user_id
+            "#,
+            token_hash,
+            EmailTokenPurpose::PasswordReset.as_str()
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(user_id) = user_id else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET password = $1
+            WHERE id = $2
+            "#,
+            password_hash,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE sessions
+            SET revoked_at = now()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // the old password may have leaked, so may anything created with it
+        sqlx::query!(
+            r#"
+            UPDATE personal_access_tokens
+            SET revoked_at = now()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(user_id))
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    common::error::{AppError, ForbiddenError, NotFoundError, ValidationError},
+    modules::{
+        user::{model::ResetPasswordCredentials, repository::UserRepo},
+        verification::{
+            model::{EmailTokenPurpose, RestrictedAction, VerificationPolicy},
+            repository::EmailTokenRepo,
+        },
+    },
+    utils::{
+        mailer::{Mail, Mailer},
+        password::{PasswordConfig, hash_password},
+        token::{generate_token, hash_token},
+    },
+};
+
+const VERIFICATION_TOKEN_TTL_HOURS: i64 = 24;
+const RESET_TOKEN_TTL_MINUTES: i64 = 30;
+const RESEND_COOLDOWN_SECONDS: i64 = 60;
+
+#[derive(Clone)]
+pub struct VerificationService {
+    pool: PgPool,
+    mailer: Arc<dyn Mailer>,
+    app_url: String,
+    policy: VerificationPolicy,
+    password_config: PasswordConfig,
+}
+
+impl VerificationService {
+    pub fn new(
+        pool: PgPool,
+        mailer: Arc<dyn Mailer>,
+        app_url: String,
+        policy: VerificationPolicy,
+        password_config: PasswordConfig,
+    ) -> Self {
+        Self {
+            pool,
+            mailer,
+            app_url,
+            policy,
+            password_config,
+        }
+    }
+
+    pub async fn send_verification(&self, user_id: &Uuid, email: &str) -> Result<(), AppError> {
+        let token = generate_token();
+        let expires_at = OffsetDateTime::now_utc() + Duration::hours(VERIFICATION_TOKEN_TTL_HOURS);
+
+        EmailTokenRepo::create(
+            &self.pool,
+            user_id,
+            EmailTokenPurpose::VerifyEmail,
+            &hash_token(&token),
+            expires_at,
+        )
+        .await?;
+
+        self.mailer
+            .send(Mail {
+                to: email.to_string(),
+                subject: "Verify your Wiki email".into(),
+                body: format!(
+                    "Welcome to Wiki!\n\nConfirm your email address by opening the link below:\n{}/verify-email?token={token}\n\nThe link expires in {VERIFICATION_TOKEN_TTL_HOURS} hours.",
+                    self.app_url
+                ),
+            })
+            .await
+    }
+
+    pub async fn resend(&self, user_id: &Uuid) -> Result<(), AppError> {
+        let user = UserRepo::fetch_by_id(&self.pool, *user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        if user.email_verified {
+            return Err(AppError::Validation(ValidationError::EmailAlreadyVerified));
+        }
+
+        let last = EmailTokenRepo::last_issued_at(&self.pool, user_id, EmailTokenPurpose::VerifyEmail)
+            .await?;
+
+        if let Some(last) = last
+            && OffsetDateTime::now_utc() - last < Duration::seconds(RESEND_COOLDOWN_SECONDS)
+        {
+            return Err(AppError::TooManyRequests(
+                "Please wait a minute before requesting another email".into(),
+            ));
+        }
+
+        self.send_verification(&user.id, &user.email).await
+    }
+
+    pub async fn verify(&self, token: &str) -> Result<(), AppError> {
+        EmailTokenRepo::verify_email(&self.pool, &hash_token(token))
+            .await?
+            .ok_or(AppError::Validation(ValidationError::InvalidOrExpiredToken))?;
+
+        Ok(())
+    }
+
+    // unknown emails are ignored so the endpoint can't be used to probe for accounts
+    pub async fn send_password_reset(&self, email: &str) -> Result<(), AppError> {
+        let Some(user) = UserRepo::fetch_by_email(&self.pool, email).await? else {
+            return Ok(());
+        };
+
+        let last = EmailTokenRepo::last_issued_at(&self.pool, &user.id, EmailTokenPurpose::PasswordReset)
+            .await?;
+
+        if let Some(last) = last
+            && OffsetDateTime::now_utc() - last < Duration::seconds(RESEND_COOLDOWN_SECONDS)
+        {
+            return Ok(());
+        }
+
+        let token = generate_token();
+        let expires_at = OffsetDateTime::now_utc() + Duration::minutes(RESET_TOKEN_TTL_MINUTES);
+
+        EmailTokenRepo::create(
+            &self.pool,
+            &user.id,
+            EmailTokenPurpose::PasswordReset,
+            &hash_token(&token),
+            expires_at,
+        )
+        .await?;
+
+        self.mailer
+            .send(Mail {
+                to: user.email,
+                subject: "Reset your Wiki password".into(),
+                body: format!(
+                    "Someone asked to reset the password of your Wiki account.\n\nChoose a new password here:\n{}/reset-password?token={token}\n\nThe link expires in {RESET_TOKEN_TTL_MINUTES} minutes. If this wasn't you, ignore this email.",
+                    self.app_url
+                ),
+            })
+            .await
+    }
+
+    pub async fn reset_password(&self, credentials: ResetPasswordCredentials) -> Result<Uuid, AppError> {
+        let password_hash = hash_password(&credentials.new_password, &self.password_config).await?;
+
+        let user_id = EmailTokenRepo::reset_password(&self.pool, &hash_token(&credentials.token), &password_hash)
+            .await?
+            .ok_or(AppError::Validation(ValidationError::InvalidOrExpiredToken))?;
+
+        Ok(user_id)
+    }
+
+    pub async fn ensure_allowed(
+        &self,
+        user_id: &Uuid,
+        action: RestrictedAction,
+    ) -> Result<(), AppError> {
+        if !self.policy.restricts(action) {
+            return Ok(());
+        }
+
+        let user = UserRepo::fetch_by_id(&self.pool, *user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(NotFoundError::UserNotFound))?;
+
+        if !user.email_verified {
+            return Err(AppError::Forbidden(ForbiddenError::EmailNotVerified));
+        }
+
+        Ok(())
+    }
+}
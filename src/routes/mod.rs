@@ -1,60 +1,184 @@
+use std::time::Duration;
+
 use axum::{
-    Router,
-    middleware::from_fn_with_state,
+    Extension, Router,
+    middleware::{from_fn, from_fn_with_state},
     routing::{delete, get, post, put},
 };
 
 use crate::{
-    middleware::auth::auth_middleware,
+    middleware::{
+        auth::{auth_middleware, optional_auth_middleware, require_scope, require_session},
+        rate_limit::{RateLimiter, rate_limit},
+    },
     modules::{
+        admin::handler::{
+            audit_log_handler, delete_message_handler, delete_room_handler, lift_suspension_handler,
+            search_users_handler, suspend_user_handler, update_role_handler,
+        },
+        audit::handler::my_audit_log_handler,
+        block::handler::{blocked_users_handler, block_user_handler, unblock_user_handler},
+        export::handler::{
+            download_export_handler, export_status_handler, request_export_handler,
+        },
+        follow::handler::{
+            accept_follow_request_handler, follow_requests_handler, follow_user_handler,
+            followers_handler, following_handler, reject_follow_request_handler,
+            unfollow_user_handler,
+        },
+        mfa::handler::{
+            confirm_totp_handler, disable_totp_handler, enroll_totp_handler,
+            second_factor_login_handler,
+        },
+        mission::handler::{
+            create_mission_handler, delete_mission_handler, fetch_all_missions_handler,
+            get_mission_handler,
+        },
+        privacy::handler::{get_privacy_handler, update_privacy_handler},
         progress::handler::{
             create_daily_progress_handler, create_daily_progress_todo_handler,
             delete_daily_progress_todo_handler, fetch_all_daily_progress_todos,
             fetch_daily_progress_todo_by_id, is_progress_exits_handler,
-            toggle_daily_progress_todo_handler,
+            toggle_daily_progress_todo_handler, user_progress_handler, user_stats_handler,
+        },
+        session::handler::{
+            list_sessions_handler, revoke_all_sessions_handler, revoke_session_handler,
         },
         rooms::handler::{
-            create_room_handler, get_all_rooms_handler, get_room_handler, get_room_membership_handler, join_room_handler, leave_room_handler, ws_handler
+            create_room_handler, get_all_rooms_handler, get_room_handler, get_room_membership_handler, join_room_handler, leave_room_handler, user_rooms_handler, ws_handler
         },
         todo::handler::{
-            create_category_handler, create_tag_handler, delete_category_handler,
-            delete_tag_handler, delete_todo_handler, fetch_all_categories_handler,
-            fetch_all_tags_handler, update_todo_handler,
+            create_category_handler, create_tag_handler, create_todo_handler,
+            delete_category_handler, delete_tag_handler, delete_todo_handler,
+            fetch_all_categories_handler, fetch_all_tags_handler, fetch_all_todos_handler,
+            due_today_todos_handler, get_todo_handler, overdue_todos_handler, patch_todo_handler,
         },
+        token::{
+            handler::{create_token_handler, fetch_all_tokens_handler, revoke_token_handler},
+            model::Resource,
+        },
+        verification::handler::{resend_verification_handler, verify_email_handler},
         user::handler::{
-            change_user_visibility_handler, create_user, delete_user_handler, get_user_by_username_handler, get_user_handler, login_user, logout
+            change_password_handler, create_user, delete_user_handler, forgot_password_handler, get_user_by_username_handler, get_user_handler, login_user, logout, refresh_handler, reset_password_handler, update_profile_handler
         },
     },
     state::AppState,
 };
 
 pub fn create_app(state: AppState) -> Router {
+    let trusted_proxies = state.trusted_proxies.clone();
+
     Router::new()
         .nest("/api", protected_routes())
         .route_layer(from_fn_with_state(state.clone(), auth_middleware))
+        .nest(
+            "/api",
+            public_routes().route_layer(from_fn_with_state(state.clone(), optional_auth_middleware)),
+        )
         .nest("/api", routes())
+        .layer(Extension(trusted_proxies))
         .with_state(state)
 }
 
 pub fn protected_routes() -> Router<AppState> {
     Router::new()
-        // .route("/todo/add", post(create_todo_handler))
-        // .route("/todo/get/{id}", get(get_todo_handler))
-        .route("/todo/update/{id}", put(update_todo_handler))
-        .route("/todo/remove/{id}", delete(delete_todo_handler))
+        .merge(account_routes())
+        .merge(user_routes())
+        .merge(todo_routes())
+        .merge(progress_routes())
+        .merge(room_routes())
+        .merge(admin_routes())
+}
+
+// account security, only reachable from a signed-in session and never with a personal access token
+fn account_routes() -> Router<AppState> {
+    Router::new()
         .route("/user/delete", delete(delete_user_handler))
-        .route("/user/me", get(get_user_handler))
         .route("/user/logout", post(logout))
+        .route("/user/password", put(change_password_handler))
+        .route(
+            "/user/sessions",
+            get(list_sessions_handler).delete(revoke_all_sessions_handler),
+        )
+        .route("/user/sessions/{session_id}", delete(revoke_session_handler))
+        .route(
+            "/user/tokens",
+            get(fetch_all_tokens_handler).post(create_token_handler),
+        )
+        .route("/user/tokens/{token_id}", delete(revoke_token_handler))
+        .route("/user/me/audit", get(my_audit_log_handler))
+        .route("/user/me/export", post(request_export_handler))
+        .route("/user/me/export/{export_id}", get(export_status_handler))
+        .route(
+            "/user/me/export/{export_id}/download",
+            get(download_export_handler),
+        )
+        .route("/user/2fa/enroll", post(enroll_totp_handler))
+        .route("/user/2fa/confirm", post(confirm_totp_handler))
+        .route("/user/2fa/disable", post(disable_totp_handler))
+        .route_layer(from_fn(require_session))
+}
+
+// every handler checks the caller's role itself through the guard extractors
+fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/users", get(search_users_handler))
+        .route("/admin/users/{user_id}/role", put(update_role_handler))
+        .route(
+            "/admin/users/{user_id}/suspend",
+            post(suspend_user_handler).delete(lift_suspension_handler),
+        )
+        .route("/admin/rooms/{room_id}", delete(delete_room_handler))
+        .route("/admin/messages/{message_id}", delete(delete_message_handler))
+        .route("/admin/audit", get(audit_log_handler))
+        .route_layer(from_fn(require_session))
+}
+
+fn user_routes() -> Router<AppState> {
+    Router::new()
+        .route("/user/me", get(get_user_handler).patch(update_profile_handler))
+        .route(
+            "/user/me/privacy",
+            get(get_privacy_handler).patch(update_privacy_handler),
+        )
+        .route("/user/verify-email/resend", post(resend_verification_handler))
         .route(
-            "/user/update_visibility",
-            put(change_user_visibility_handler),
+            "/user/{username}/follow",
+            post(follow_user_handler).delete(unfollow_user_handler),
         )
+        .route("/user/me/follow-requests", get(follow_requests_handler))
+        .route(
+            "/user/me/follow-requests/{username}",
+            post(accept_follow_request_handler).delete(reject_follow_request_handler),
+        )
+        .route(
+            "/user/{username}/block",
+            post(block_user_handler).delete(unblock_user_handler),
+        )
+        .route("/user/me/blocks", get(blocked_users_handler))
+        .route_layer(from_fn_with_state(Resource::User, require_scope))
+}
+
+fn todo_routes() -> Router<AppState> {
+    Router::new()
+        .route("/todos", get(fetch_all_todos_handler).post(create_todo_handler))
+        .route("/todos/overdue", get(overdue_todos_handler))
+        .route("/todos/due-today", get(due_today_todos_handler))
+        .route("/todos/{todo_id}", get(get_todo_handler).patch(patch_todo_handler))
+        // kept for older clients, same body as PATCH /todos/{todo_id}
+        .route("/todo/update/{todo_id}", put(patch_todo_handler))
+        .route("/todo/remove/{id}", delete(delete_todo_handler))
         .route("/tag/add", post(create_tag_handler))
         .route("/tag/{slug}", delete(delete_tag_handler))
         .route("/tag/all", get(fetch_all_tags_handler))
         .route("/category/add", post(create_category_handler))
         .route("/category/{slug}", delete(delete_category_handler))
         .route("/category/all", get(fetch_all_categories_handler))
+        .route_layer(from_fn_with_state(Resource::Todos, require_scope))
+}
+
+fn progress_routes() -> Router<AppState> {
+    Router::new()
         .route("/progress", post(create_daily_progress_handler))
         .route(
             "/progress/todo/create/{daily_progress_id}",
@@ -71,6 +195,16 @@ pub fn protected_routes() -> Router<AppState> {
             get(fetch_all_daily_progress_todos),
         )
         .route("/progress/is_exits/{day}", get(is_progress_exits_handler))
+        .route("/missions", get(fetch_all_missions_handler).post(create_mission_handler))
+        .route(
+            "/missions/{mission_id}",
+            get(get_mission_handler).delete(delete_mission_handler),
+        )
+        .route_layer(from_fn_with_state(Resource::Progress, require_scope))
+}
+
+fn room_routes() -> Router<AppState> {
+    Router::new()
         .route("/room", post(create_room_handler))
         .route("/room/info/{room_id}", get(get_room_handler))
         .route("/rooms", get(get_all_rooms_handler))
@@ -78,11 +212,51 @@ pub fn protected_routes() -> Router<AppState> {
         .route("/room/{room_id}/join", post(join_room_handler))
         .route("/room/{room_id}/leave", post(leave_room_handler))
         .route("/room/{room_id}/membership", get(get_room_membership_handler))
+        .route_layer(from_fn_with_state(Resource::Rooms, require_scope))
+}
+
+// readable by anyone, the requester is known when they send a token
+pub fn public_routes() -> Router<AppState> {
+    Router::new()
+        .route("/user/{username}", get(get_user_by_username_handler))
+        .route("/user/{username}/followers", get(followers_handler))
+        .route("/user/{username}/following", get(following_handler))
+        .route("/user/{username}/progress", get(user_progress_handler))
+        .route("/user/{username}/stats", get(user_stats_handler))
+        .route("/user/{username}/rooms", get(user_rooms_handler))
 }
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/user/create", post(create_user))
         .route("/user/login", post(login_user))
-        .route("/user/{username}", get(get_user_by_username_handler))
-}
\ No newline at end of file
+        .route(
+            "/user/login/2fa",
+            post(second_factor_login_handler).route_layer(from_fn_with_state(
+                RateLimiter::new(10, Duration::from_secs(15 * 60), "challenge_token"),
+                rate_limit,
+            )),
+        )
+        .route("/user/refresh", post(refresh_handler))
+        .route("/user/verify-email", post(verify_email_handler))
+        .merge(password_reset_routes())
+}
+
+// unauthenticated and able to send email, so each client ip and each target gets a small budget
+fn password_reset_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/user/password/forgot",
+            post(forgot_password_handler).route_layer(from_fn_with_state(
+                RateLimiter::new(5, Duration::from_secs(15 * 60), "email"),
+                rate_limit,
+            )),
+        )
+        .route(
+            "/user/password/reset",
+            post(reset_password_handler).route_layer(from_fn_with_state(
+                RateLimiter::new(10, Duration::from_secs(15 * 60), "token"),
+                rate_limit,
+            )),
+        )
+}
@@ -1,4 +1,4 @@
-use std::{collections::{HashMap}, sync::Arc};
+use std::{collections::{HashMap, HashSet}, sync::Arc};
 
 use jsonwebtoken::{DecodingKey, EncodingKey};
 use serde::{Deserialize, Serialize};
@@ -6,7 +6,11 @@ use sqlx::{PgPool};
 use uuid::Uuid;
 use tokio::sync::{Mutex, mpsc};
 
-use crate::modules::{progress::service::ProgressService, rooms::{model::{ServerEvent}, service::RoomService}, todo::service::TodoService, user::service::UserService};
+use crate::{
+    modules::{admin::service::AdminService, block::service::BlockService, export::service::ExportService, follow::service::FollowService, mission::service::MissionService, privacy::service::PrivacyService, user::model::Role, audit::service::AuditService, lockout::service::LockoutService, mfa::service::MfaService, progress::service::ProgressService, rooms::{model::{ServerEvent}, service::RoomService}, session::service::SessionService, todo::service::TodoService, token::service::TokenService, user::service::UserService, verification::{model::VerificationPolicy, service::VerificationService}},
+    middleware::client_info::TrustedProxies,
+    utils::{config::Config, mailer::{Mailer, mailer_from_env}, password::PasswordConfig},
+};
 
 type RoomId = uuid::Uuid;
 type UserId = uuid::Uuid;
@@ -14,7 +18,9 @@ type UserId = uuid::Uuid;
 #[derive(Clone)]
 pub struct Member {
     pub username: String,
-    pub tx: mpsc::Sender<ServerEvent>
+    pub tx: mpsc::Sender<ServerEvent>,
+    // users this member blocked, loaded on join and kept in sync by the block handlers
+    pub blocked: HashSet<UserId>,
 }
 
 #[derive(Clone)]
@@ -31,15 +37,97 @@ pub struct AppState {
     pub user_service: UserService,
     pub progress_service: ProgressService,
     pub room_service: RoomService,
+    pub session_service: SessionService,
+    pub token_service: TokenService,
+    pub mfa_service: MfaService,
+    pub lockout_service: LockoutService,
+    pub audit_service: AuditService,
+    pub admin_service: AdminService,
+    pub export_service: ExportService,
+    pub follow_service: FollowService,
+    pub block_service: BlockService,
+    pub privacy_service: PrivacyService,
+    pub mission_service: MissionService,
+    pub verification_service: VerificationService,
+    pub trusted_proxies: TrustedProxies,
     pub rooms: Arc<Mutex<HashMap<RoomId, RoomState>>>
 }
 
+// everything AppState is built from besides the pool
+pub struct AppSettings {
+    pub jwt_secret: String,
+    pub password_config: PasswordConfig,
+    pub app_url: String,
+    pub mailer: Arc<dyn Mailer>,
+    pub verification_policy: VerificationPolicy,
+    pub deletion_grace_days: i32,
+    pub export_dir: String,
+    pub trusted_proxies: TrustedProxies,
+}
+
+impl AppSettings {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            jwt_secret: Config::JsonWebTokenSecret.from_env()?,
+            password_config: PasswordConfig::from_env()?,
+            app_url: Config::AppUrl.parse_or("http://localhost:3001".to_string())?,
+            mailer: mailer_from_env()?,
+            verification_policy: VerificationPolicy::from_env()?,
+            deletion_grace_days: Config::AccountDeletionGraceDays.parse_or(14)?,
+            export_dir: Config::ExportDir.parse_or("exports".to_string())?,
+            trusted_proxies: TrustedProxies::from_env()?,
+        })
+    }
+}
+
+impl AppState {
+    pub fn new(pool: PgPool, settings: AppSettings) -> Self {
+        let audit_service = AuditService::new(pool.clone());
+
+        Self {
+            pool: pool.clone(),
+            jwt_decoding: DecodingKey::from_secret(settings.jwt_secret.as_bytes()),
+            jwt_encoding: EncodingKey::from_secret(settings.jwt_secret.as_bytes()),
+            todo_service: TodoService::new(pool.clone()),
+            user_service: UserService::new(
+                pool.clone(),
+                settings.password_config.clone(),
+                settings.deletion_grace_days,
+            ),
+            progress_service: ProgressService::new(pool.clone()),
+            room_service: RoomService::new(pool.clone()),
+            session_service: SessionService::new(pool.clone()),
+            token_service: TokenService::new(pool.clone()),
+            mfa_service: MfaService::new(pool.clone(), settings.password_config.clone()),
+            lockout_service: LockoutService::new(pool.clone(), audit_service.clone()),
+            audit_service,
+            admin_service: AdminService::new(pool.clone()),
+            export_service: ExportService::new(pool.clone(), settings.export_dir),
+            follow_service: FollowService::new(pool.clone()),
+            block_service: BlockService::new(pool.clone()),
+            privacy_service: PrivacyService::new(pool.clone()),
+            mission_service: MissionService::new(pool.clone()),
+            verification_service: VerificationService::new(
+                pool,
+                settings.mailer,
+                settings.app_url,
+                settings.verification_policy,
+                settings.password_config,
+            ),
+            trusted_proxies: settings.trusted_proxies,
+            rooms: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
     pub user_id: Uuid,
+    pub session_id: Uuid,
     pub name: String,
     pub username: String,
     pub email: String,
+    pub role: Role,
     pub exp: usize, // expiry timestamp
     pub iat: usize, // current timestamp
 }
@@ -1,20 +1,63 @@
-use std::env;
+use std::{env, str::FromStr};
 
 use crate::common::error::AppError;
 
 pub enum Config {
     DatabaseUrl,
-    JsonWebTokenSecret
+    JsonWebTokenSecret,
+    Argon2MemoryKib,
+    Argon2Iterations,
+    Argon2Parallelism,
+    AppUrl,
+    MailBackend,
+    MailFrom,
+    MailDir,
+    SmtpHost,
+    SmtpPort,
+    SmtpUsername,
+    SmtpPassword,
+    UnverifiedRestrictions,
+    AccountDeletionGraceDays,
+    ExportDir,
+    TrustedProxies,
 }
 
 impl Config {
-    pub fn from_env(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let key = match self {
+    fn key(&self) -> &'static str {
+        match self {
             Config::DatabaseUrl => "DATABASE_URL",
-            Config::JsonWebTokenSecret => "JWT_SECRET"
-        };
-        let value = env::var(key).map_err(|e| AppError::Failed(e.to_string()))?;
+            Config::JsonWebTokenSecret => "JWT_SECRET",
+            Config::Argon2MemoryKib => "ARGON2_MEMORY_KIB",
+            Config::Argon2Iterations => "ARGON2_ITERATIONS",
+            Config::Argon2Parallelism => "ARGON2_PARALLELISM",
+            Config::AppUrl => "APP_URL",
+            Config::MailBackend => "MAIL_BACKEND",
+            Config::MailFrom => "MAIL_FROM",
+            Config::MailDir => "MAIL_DIR",
+            Config::SmtpHost => "SMTP_HOST",
+            Config::SmtpPort => "SMTP_PORT",
+            Config::SmtpUsername => "SMTP_USERNAME",
+            Config::SmtpPassword => "SMTP_PASSWORD",
+            Config::UnverifiedRestrictions => "UNVERIFIED_RESTRICTIONS",
+            Config::AccountDeletionGraceDays => "ACCOUNT_DELETION_GRACE_DAYS",
+            Config::ExportDir => "EXPORT_DIR",
+            Config::TrustedProxies => "TRUSTED_PROXIES",
+        }
+    }
+
+    pub fn from_env(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let value = env::var(self.key()).map_err(|e| AppError::Failed(e.to_string()))?;
 
         Ok(value)
     }
-}
\ No newline at end of file
+
+    // optional settings fall back to `default` when unset, but a malformed value is an error
+    pub fn parse_or<T: FromStr>(&self, default: T) -> Result<T, Box<dyn std::error::Error>> {
+        match env::var(self.key()) {
+            Ok(value) => value
+                .parse()
+                .map_err(|_| AppError::Failed(format!("Invalid value for {}", self.key())).into()),
+            Err(_) => Ok(default),
+        }
+    }
+}
@@ -3,18 +3,23 @@ use chrono::{Duration as DurationC, Utc};
 use jsonwebtoken::{DecodingKey, EncodingKey, Header, TokenData, Validation, decode, encode, errors::Result};
 use uuid::Uuid;
 
-use crate::state::{Claims};
+use crate::{modules::user::model::Role, state::Claims};
 
-pub async fn create_jwt_token(user_id: Uuid, name: String, username: String, email: String, encoding_key: EncodingKey) -> Result<String> {
+// access tokens are short lived, the session's refresh token keeps the user signed in
+pub const ACCESS_TOKEN_TTL_MINUTES: i64 = 15;
+
+pub async fn create_jwt_token(user_id: Uuid, session_id: Uuid, name: String, username: String, email: String, role: Role, encoding_key: EncodingKey) -> Result<String> {
     let now = Utc::now();
 
     let claims = Claims {
         user_id: user_id,
+        session_id,
         email,
         name,
         username,
+        role,
         iat: now.timestamp() as usize,
-        exp: (now + DurationC::days(7)).timestamp() as usize,
+        exp: (now + DurationC::minutes(ACCESS_TOKEN_TTL_MINUTES)).timestamp() as usize,
     };
 
     let token = encode(
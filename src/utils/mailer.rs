@@ -0,0 +1,149 @@
+use std::{path::PathBuf, sync::Arc};
+
+use futures::future::BoxFuture;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{common::error::AppError, utils::config::Config};
+
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+pub trait Mailer: Send + Sync {
+    fn send(&self, mail: Mail) -> BoxFuture<'_, Result<(), AppError>>;
+}
+
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: String,
+        password: String,
+        from: &str,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+            .port(port)
+            .credentials(Credentials::new(username, password))
+            .build();
+
+        Ok(Self {
+            from: from.parse()?,
+            transport,
+        })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    fn send(&self, mail: Mail) -> BoxFuture<'_, Result<(), AppError>> {
+        Box::pin(async move {
+            let to: Mailbox = mail
+                .to
+                .parse()
+                .map_err(|_| AppError::Failed("Invalid recipient address".into()))?;
+
+            let message = Message::builder()
+                .from(self.from.clone())
+                .to(to)
+                .subject(mail.subject)
+                .header(ContentType::TEXT_PLAIN)
+                .body(mail.body)
+                .map_err(|e| AppError::Failed(format!("Failed to build email: {e}")))?;
+
+            self.transport
+                .send(message)
+                .await
+                .map_err(|e| AppError::Failed(format!("Failed to send email: {e}")))?;
+
+            Ok(())
+        })
+    }
+}
+
+// local development stand-in, every mail becomes a file in `dir`
+pub struct FileMailer {
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, mail: Mail) -> BoxFuture<'_, Result<(), AppError>> {
+        Box::pin(async move {
+            tokio::fs::create_dir_all(&self.dir)
+                .await
+                .map_err(|e| AppError::Failed(format!("Failed to create mail dir: {e}")))?;
+
+            let name = format!(
+                "{}-{}.eml",
+                OffsetDateTime::now_utc().unix_timestamp(),
+                Uuid::new_v4()
+            );
+            let content = format!(
+                "To: {}\nSubject: {}\n\n{}\n",
+                mail.to, mail.subject, mail.body
+            );
+
+            tokio::fs::write(self.dir.join(name), content)
+                .await
+                .map_err(|e| AppError::Failed(format!("Failed to write email: {e}")))?;
+
+            Ok(())
+        })
+    }
+}
+
+pub struct StdoutMailer;
+
+impl Mailer for StdoutMailer {
+    fn send(&self, mail: Mail) -> BoxFuture<'_, Result<(), AppError>> {
+        Box::pin(async move {
+            println!(
+                "---- email to {} ----\nSubject: {}\n\n{}\n----",
+                mail.to, mail.subject, mail.body
+            );
+
+            Ok(())
+        })
+    }
+}
+
+// MAIL_BACKEND picks the implementation: smtp, file or stdout (default)
+pub fn mailer_from_env() -> Result<Arc<dyn Mailer>, Box<dyn std::error::Error>> {
+    let backend = Config::MailBackend.parse_or("stdout".to_string())?;
+
+    let mailer: Arc<dyn Mailer> = match backend.as_str() {
+        "smtp" => Arc::new(SmtpMailer::new(
+            &Config::SmtpHost.from_env()?,
+            Config::SmtpPort.parse_or(587)?,
+            Config::SmtpUsername.from_env()?,
+            Config::SmtpPassword.from_env()?,
+            &Config::MailFrom.from_env()?,
+        )?),
+        "file" => Arc::new(FileMailer::new(
+            Config::MailDir.parse_or("mail".to_string())?,
+        )),
+        "stdout" => Arc::new(StdoutMailer),
+        other => {
+            return Err(AppError::Failed(format!("Unknown MAIL_BACKEND: {other}")).into());
+        }
+    };
+
+    Ok(mailer)
+}
@@ -1,4 +1,6 @@
 pub mod db;
 pub mod jwt;
+pub mod mailer;
 pub mod password;
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod token;
\ No newline at end of file
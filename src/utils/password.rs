@@ -1,9 +1,128 @@
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use subtle::ConstantTimeEq;
 
+use crate::{common::error::AppError, utils::config::Config};
 
-// pub async fn hash_password(password: &str) {
+const ARGON2_PREFIX: &str = "$argon2";
 
-// }
+#[derive(Debug, Clone)]
+pub struct PasswordConfig {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
 
-// pub async fn verify_password(password: &str) {
-    
-// }
\ No newline at end of file
+#[derive(Debug, PartialEq, Eq)]
+pub enum PasswordCheck {
+    Invalid,
+    Valid,
+    // password is correct but the stored value is plaintext or uses outdated params
+    ValidNeedsRehash,
+}
+
+impl Default for PasswordConfig {
+    // OWASP recommended minimum for argon2id
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl PasswordConfig {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let default = Self::default();
+
+        let config = Self {
+            memory_kib: Config::Argon2MemoryKib.parse_or(default.memory_kib)?,
+            iterations: Config::Argon2Iterations.parse_or(default.iterations)?,
+            parallelism: Config::Argon2Parallelism.parse_or(default.parallelism)?,
+        };
+
+        config.argon2()?;
+
+        Ok(config)
+    }
+
+    fn argon2(&self) -> Result<Argon2<'static>, AppError> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| AppError::Failed(format!("Invalid argon2 params: {e}")))?;
+
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    fn is_current(&self, hash: &PasswordHash) -> bool {
+        let Ok(params) = Params::try_from(hash) else {
+            return false;
+        };
+
+        hash.algorithm == Algorithm::Argon2id.ident()
+            && params.m_cost() == self.memory_kib
+            && params.t_cost() == self.iterations
+            && params.p_cost() == self.parallelism
+    }
+}
+
+pub async fn hash_password(password: &str, config: &PasswordConfig) -> Result<String, AppError> {
+    let password = password.to_owned();
+    let config = config.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+
+        config
+            .argon2()?
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|_| AppError::Failed("Failed to hash password".into()))
+    })
+    .await
+    .map_err(|_| AppError::Failed("Failed to hash password".into()))?
+}
+
+pub async fn verify_password(
+    password: &str,
+    stored: &str,
+    config: &PasswordConfig,
+) -> Result<PasswordCheck, AppError> {
+    // rows created before hashing was introduced still hold the raw password
+    if !stored.starts_with(ARGON2_PREFIX) {
+        let matches: bool = password.as_bytes().ct_eq(stored.as_bytes()).into();
+
+        return Ok(if matches {
+            PasswordCheck::ValidNeedsRehash
+        } else {
+            PasswordCheck::Invalid
+        });
+    }
+
+    let password = password.to_owned();
+    let stored = stored.to_owned();
+    let config = config.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let hash = PasswordHash::new(&stored)
+            .map_err(|_| AppError::Failed("Stored password hash is malformed".into()))?;
+
+        // argon2 compares digests in constant time
+        if Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_err()
+        {
+            return Ok(PasswordCheck::Invalid);
+        }
+
+        if config.is_current(&hash) {
+            Ok(PasswordCheck::Valid)
+        } else {
+            Ok(PasswordCheck::ValidNeedsRehash)
+        }
+    })
+    .await
+    .map_err(|_| AppError::Failed("Failed to verify password".into()))?
+}
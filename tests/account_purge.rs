@@ -0,0 +1,53 @@
+mod common;
+
+use std::{path::Path, time::Duration};
+
+use axum::http::{Method, StatusCode};
+use protfolio_backend::jobs::account_purge;
+
+use common::{TestApp, id};
+
+#[tokio::test]
+async fn a_purge_removes_the_export_archives() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+
+    let (status, body) = app.request(&user, Method::POST, "/api/user/me/export", None).await;
+    assert!(status.is_success(), "{body}");
+    let export_id = id(&body);
+
+    // the archive is built in the background
+    let mut ready = false;
+    for _ in 0..50 {
+        let (_, body) = app
+            .request(&user, Method::GET, &format!("/api/user/me/export/{export_id}"), None)
+            .await;
+        if body["data"]["status"] == "ready" {
+            ready = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(ready, "the export never finished");
+
+    let file_path: String = sqlx::query_scalar("SELECT file_path FROM data_exports WHERE user_id = $1")
+        .bind(user.id)
+        .fetch_one(&app.state.pool)
+        .await
+        .unwrap();
+    assert!(Path::new(&file_path).exists());
+
+    // past the grace period
+    sqlx::query("UPDATE users SET deleted_at = now() - interval '30 days' WHERE id = $1")
+        .bind(user.id)
+        .execute(&app.state.pool)
+        .await
+        .unwrap();
+
+    account_purge::purge(&app.state).await.unwrap();
+
+    assert!(!Path::new(&file_path).exists());
+
+    let (status, _) = app.request(&user, Method::GET, "/api/user/me", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
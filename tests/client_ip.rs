@@ -0,0 +1,35 @@
+use std::net::IpAddr;
+
+use protfolio_backend::middleware::client_info::TrustedProxies;
+
+fn ip(address: &str) -> IpAddr {
+    address.parse().unwrap()
+}
+
+#[test]
+fn forwarded_for_is_ignored_from_untrusted_peers() {
+    let proxies = TrustedProxies::new(vec![ip("10.0.0.1")]);
+
+    let client = proxies.client_ip(Some(ip("203.0.113.7")), Some("198.51.100.1"));
+
+    assert_eq!(client, Some(ip("203.0.113.7")));
+}
+
+#[test]
+fn the_right_most_untrusted_hop_is_the_client() {
+    let proxies = TrustedProxies::new(vec![ip("10.0.0.1"), ip("10.0.0.2")]);
+
+    // the client made up the first entry, the proxies appended the rest
+    let client = proxies.client_ip(Some(ip("10.0.0.1")), Some("1.2.3.4, 203.0.113.7, 10.0.0.2"));
+
+    assert_eq!(client, Some(ip("203.0.113.7")));
+}
+
+#[test]
+fn a_trusted_peer_without_a_usable_header_is_the_client() {
+    let proxies = TrustedProxies::new(vec![ip("10.0.0.1")]);
+
+    assert_eq!(proxies.client_ip(Some(ip("10.0.0.1")), None), Some(ip("10.0.0.1")));
+    assert_eq!(proxies.client_ip(Some(ip("10.0.0.1")), Some("garbage")), Some(ip("10.0.0.1")));
+    assert_eq!(proxies.client_ip(None, Some("203.0.113.7")), None);
+}
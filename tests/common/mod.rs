@@ -0,0 +1,244 @@
+// shared by every test binary, each one only uses part of it
+#![allow(dead_code)]
+
+use std::{str::FromStr, sync::Arc};
+
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Method, Request, StatusCode, header},
+};
+use protfolio_backend::{
+    middleware::client_info::TrustedProxies,
+    modules::verification::model::VerificationPolicy,
+    routes::create_app,
+    state::{AppSettings, AppState},
+    utils::{mailer::StdoutMailer, password::PasswordConfig},
+};
+use serde_json::{Value, json};
+use sqlx::{
+    Connection, Executor, PgConnection, PgPool,
+    postgres::PgConnectOptions,
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub struct TestApp {
+    // for driving background jobs directly
+    pub state: AppState,
+    router: Router,
+}
+
+pub struct TestUser {
+    pub id: Uuid,
+    pub email: String,
+    jwt: String,
+}
+
+impl TestApp {
+    // every test shares `<DATABASE_URL database>_test`, so users get random names instead of a clean slate
+    pub async fn spawn() -> Self {
+        dotenvy::dotenv().ok();
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for integration tests");
+        let options = PgConnectOptions::from_str(&url).expect("invalid DATABASE_URL");
+        let test_db = format!("{}_test", options.get_database().unwrap_or("postgres"));
+
+        let mut admin = PgConnection::connect_with(&options.clone().database("postgres"))
+            .await
+            .expect("failed to connect to postgres");
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_database WHERE datname = $1)")
+            .bind(&test_db)
+            .fetch_one(&mut admin)
+            .await
+            .expect("failed to look up the test database");
+
+        // another test may create it first
+        if !exists {
+            let _ = admin.execute(format!(r#"CREATE DATABASE "{test_db}""#).as_str()).await;
+        }
+
+        let pool = PgPool::connect_with(options.database(&test_db))
+            .await
+            .expect("failed to connect to the test database");
+
+        sqlx::migrate!().run(&pool).await.expect("failed to run migrations");
+
+        let settings = AppSettings {
+            jwt_secret: "integration-test-secret".into(),
+            // the cheapest valid argon2 params, hashing strength isn't under test
+            password_config: PasswordConfig {
+                memory_kib: 8,
+                iterations: 1,
+                parallelism: 1,
+            },
+            app_url: "http://localhost:3001".into(),
+            mailer: Arc::new(StdoutMailer),
+            verification_policy: VerificationPolicy::from_env().expect("invalid UNVERIFIED_RESTRICTIONS"),
+            deletion_grace_days: 14,
+            export_dir: std::env::temp_dir().join("wiki-test-exports").to_string_lossy().into_owned(),
+            trusted_proxies: TrustedProxies::default(),
+        };
+
+        let state = AppState::new(pool, settings);
+
+        Self {
+            router: create_app(state.clone()),
+            state,
+        }
+    }
+
+    pub async fn sign_up(&self) -> TestUser {
+        let username = format!("u{}", &Uuid::new_v4().simple().to_string()[..12]);
+        let email = format!("{username}@example.com");
+
+        let response = self
+            .router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/user/create")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        json!({
+                            "name": username,
+                            "username": username,
+                            "email": email,
+                            "password": "Passw0rd!23",
+                        })
+                        .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK, "sign up failed");
+
+        let headers = response.headers().clone();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        let id = body["data"]["id"].as_str().and_then(|id| id.parse().ok()).expect("sign up didn't return the user id");
+
+        let jwt = headers
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|value| value.strip_prefix("jwt="))
+            .and_then(|value| value.split(';').next())
+            .expect("sign up didn't set the jwt cookie")
+            .to_string();
+
+        TestUser { id, email, jwt }
+    }
+
+    pub async fn request(
+        &self,
+        user: &TestUser,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        self.send(Some((header::COOKIE, format!("jwt={}", user.jwt))), method, uri, body).await
+    }
+
+    pub async fn request_anonymous(&self, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        self.send(None, method, uri, body).await
+    }
+
+    // signed in with a personal access token instead of the session cookie
+    pub async fn request_with_token(
+        &self,
+        token: &str,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        self.send(Some((header::AUTHORIZATION, format!("Bearer {token}"))), method, uri, body).await
+    }
+
+    async fn send(
+        &self,
+        credentials: Option<(header::HeaderName, String)>,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+
+        if let Some((name, value)) = credentials {
+            request = request.header(name, value);
+        }
+
+        let body = match body {
+            Some(body) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(body.to_string())
+            }
+            None => Body::empty(),
+        };
+
+        let response = self.router.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+}
+
+// seeding through the api, each helper fails the test when its request is rejected
+
+pub async fn seed_category(app: &TestApp, user: &TestUser, slug: &str) {
+    let (status, body) = app
+        .request(user, Method::POST, "/api/category/add", Some(json!({ "name": slug, "slug": slug })))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+pub async fn seed_tag(app: &TestApp, user: &TestUser, slug: &str) {
+    let (status, body) = app
+        .request(user, Method::POST, "/api/tag/add", Some(json!({ "name": slug, "slug": slug })))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+// `fields` is merged into the request, for tags, planning fields or a description of its own
+pub async fn create_todo(app: &TestApp, user: &TestUser, title: &str, category_slug: &str, fields: Value) -> String {
+    let mut body = json!({
+        "todo": title,
+        "description": "Chapters one to three",
+        "category_slug": category_slug,
+    });
+    body.as_object_mut().unwrap().extend(fields.as_object().unwrap().clone());
+
+    let (status, body) = app.request(user, Method::POST, "/api/todos", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    id(&body)
+}
+
+// the daily progress of `day`, opening it if needed
+pub async fn open_day(app: &TestApp, user: &TestUser, day: &str) -> String {
+    let (status, body) = app
+        .request(user, Method::POST, "/api/progress", Some(json!({ "day": day })))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    id(&body)
+}
+
+pub fn id(body: &Value) -> String {
+    body["data"]["id"].as_str().expect("response has no id").to_string()
+}
+
+// titles of a todo list response, in the order they came back
+pub fn titles(body: &Value) -> Vec<&str> {
+    body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["title"].as_str().unwrap())
+        .collect()
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::{Value, json};
+use time::macros::date;
+
+use common::{TestApp, TestUser, id, open_day, seed_category};
+
+async fn create_mission(app: &TestApp, user: &TestUser, title: &str, schedule: Value) -> String {
+    let (status, body) = app
+        .request(
+            user,
+            Method::POST,
+            "/api/missions",
+            Some(json!({
+                "todo": title,
+                "description": "Every time it comes up",
+                "category_slug": "learning",
+                "schedule": schedule,
+                "starts_on": "2030-01-01",
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    id(&body)
+}
+
+async fn day_titles(app: &TestApp, user: &TestUser, progress_id: &str) -> Vec<String> {
+    let (status, body) = app
+        .request(user, Method::GET, &format!("/api/progress/todos/{progress_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let mut titles: Vec<String> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["todo_title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[tokio::test]
+async fn new_days_start_with_matching_missions() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    seed_category(&app, &user, "learning").await;
+
+    create_mission(&app, &user, "Daily reading", json!({ "frequency": "daily" })).await;
+    create_mission(&app, &user, "Weekday kata", json!({ "frequency": "weekdays" })).await;
+    create_mission(&app, &user, "Gym sessions", json!({ "frequency": "weekly", "by_day": ["MO", "WE", "FR"] })).await;
+    create_mission(&app, &user, "Spaced review", json!({ "frequency": "interval", "every_days": 3 })).await;
+
+    // 2030-01-01 is a tuesday
+    let wednesday = open_day(&app, &user, "2030-01-02").await;
+    assert_eq!(day_titles(&app, &user, &wednesday).await, ["Daily reading", "Gym sessions", "Weekday kata"]);
+
+    let friday = open_day(&app, &user, "2030-01-04").await;
+    assert_eq!(
+        day_titles(&app, &user, &friday).await,
+        ["Daily reading", "Gym sessions", "Spaced review", "Weekday kata"]
+    );
+
+    let saturday = open_day(&app, &user, "2030-01-05").await;
+    assert_eq!(day_titles(&app, &user, &saturday).await, ["Daily reading"]);
+
+    // missions don't reach back before they start
+    let before = open_day(&app, &user, "2029-12-31").await;
+    assert!(day_titles(&app, &user, &before).await.is_empty());
+}
+
+#[tokio::test]
+async fn the_job_opens_days_and_never_duplicates_missions() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    seed_category(&app, &user, "learning").await;
+    create_mission(&app, &user, "Daily reading", json!({ "frequency": "daily" })).await;
+
+    // a day the user opened already has the mission, the job leaves it alone
+    let opened = open_day(&app, &user, "2031-05-01").await;
+    app.state.mission_service.populate(date!(2031 - 05 - 01)).await.unwrap();
+    assert_eq!(day_titles(&app, &user, &opened).await, ["Daily reading"]);
+
+    // a removed occurrence stays removed
+    let (_, body) = app
+        .request(&user, Method::GET, &format!("/api/progress/todos/{opened}"), None)
+        .await;
+    let progress_todo_id = body["data"][0]["daily_progress_todo_id"].as_str().unwrap();
+    let (status, _) = app
+        .request(&user, Method::DELETE, &format!("/api/progress/todo/{progress_todo_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    app.state.mission_service.populate(date!(2031 - 05 - 01)).await.unwrap();
+    assert!(day_titles(&app, &user, &opened).await.is_empty());
+
+    // a day nobody opened yet is created by the job
+    app.state.mission_service.populate(date!(2031 - 05 - 02)).await.unwrap();
+    let (status, body) = app
+        .request(&user, Method::GET, "/api/progress/is_exits/2031-05-02", None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let created = body["data"]["id"].as_str().unwrap().to_string();
+    assert_eq!(day_titles(&app, &user, &created).await, ["Daily reading"]);
+
+    // opening it afterwards hands back the same day
+    assert_eq!(open_day(&app, &user, "2031-05-02").await, created);
+    assert_eq!(day_titles(&app, &user, &created).await, ["Daily reading"]);
+}
+
+#[tokio::test]
+async fn the_job_skips_deleted_accounts() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    seed_category(&app, &user, "learning").await;
+    create_mission(&app, &user, "Daily reading", json!({ "frequency": "daily" })).await;
+
+    let (status, _) = app.request(&user, Method::DELETE, "/api/user/delete", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    app.state.mission_service.populate(date!(2031 - 06 - 01)).await.unwrap();
+
+    let opened: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM daily_progress WHERE user_id = $1")
+        .bind(user.id)
+        .fetch_one(&app.state.pool)
+        .await
+        .unwrap();
+    assert_eq!(opened, 0);
+}
+
+#[tokio::test]
+async fn missions_are_validated_and_private() {
+    let app = TestApp::spawn().await;
+    let owner = app.sign_up().await;
+    let other = app.sign_up().await;
+    seed_category(&app, &owner, "learning").await;
+
+    for schedule in [
+        json!({ "frequency": "weekly", "by_day": [] }),
+        json!({ "frequency": "interval", "every_days": 0 }),
+        json!({ "frequency": "hourly" }),
+    ] {
+        let (status, _) = app
+            .request(
+                &owner,
+                Method::POST,
+                "/api/missions",
+                Some(json!({ "todo": "Never scheduled", "description": "Rejected", "category_slug": "learning", "schedule": schedule })),
+            )
+            .await;
+        assert!(status.is_client_error(), "{schedule} was accepted");
+    }
+
+    let mission_id = create_mission(&app, &owner, "Weekly sync", json!({ "frequency": "weekly", "by_day": ["FR", "MO", "FR"] })).await;
+
+    let (_, body) = app.request(&owner, Method::GET, &format!("/api/missions/{mission_id}"), None).await;
+    assert_eq!(body["data"]["schedule"], json!({ "frequency": "weekly", "by_day": ["MO", "FR"] }));
+    assert_eq!(body["data"]["starts_on"], "2030-01-01");
+
+    let (status, _) = app.request(&other, Method::GET, &format!("/api/missions/{mission_id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app.request(&other, Method::DELETE, &format!("/api/missions/{mission_id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app.request(&owner, Method::DELETE, &format!("/api/missions/{mission_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = app.request(&owner, Method::GET, "/api/missions", None).await;
+    assert_eq!(body["data"], json!([]));
+}
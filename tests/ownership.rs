@@ -0,0 +1,160 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+
+use common::{TestApp, TestUser, create_todo, open_day, seed_category, seed_tag};
+
+// a category, a tag and a todo using both, all owned by `user`
+async fn seed_todo(app: &TestApp, user: &TestUser) -> String {
+    seed_category(app, user, "learning").await;
+    seed_tag(app, user, "rust").await;
+
+    create_todo(app, user, "Read the async book", "learning", json!({ "tags_slug": ["rust"] })).await
+}
+
+// a daily progress with one todo, returns the progress id and the progress todo id
+async fn seed_progress(app: &TestApp, user: &TestUser) -> (String, String) {
+    seed_category(app, user, "daily").await;
+    let progress_id = open_day(app, user, "2026-01-15").await;
+
+    let (status, _) = app
+        .request(
+            user,
+            Method::POST,
+            &format!("/api/progress/todo/create/{progress_id}"),
+            Some(json!({ "todo": "Practice lifetimes", "description": "Two exercises", "category_slug": "daily" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app
+        .request(user, Method::GET, &format!("/api/progress/todos/{progress_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let progress_todo_id = body["data"][0]["daily_progress_todo_id"].as_str().unwrap().to_string();
+
+    (progress_id, progress_todo_id)
+}
+
+#[tokio::test]
+async fn todos_of_other_users_are_not_found() {
+    let app = TestApp::spawn().await;
+    let owner = app.sign_up().await;
+    let other = app.sign_up().await;
+    let todo_id = seed_todo(&app, &owner).await;
+
+    let (status, _) = app.request(&other, Method::GET, &format!("/api/todos/{todo_id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app
+        .request(&other, Method::PATCH, &format!("/api/todos/{todo_id}"), Some(json!({ "todo": "Taken over by someone else" })))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app.request(&other, Method::DELETE, &format!("/api/todo/remove/{todo_id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = app.request(&other, Method::GET, "/api/todos", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!([]));
+
+    // nothing above touched the owner's todo
+    let (status, body) = app.request(&owner, Method::GET, &format!("/api/todos/{todo_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["title"], "Read the async book");
+}
+
+#[tokio::test]
+async fn owners_can_delete_their_todos() {
+    let app = TestApp::spawn().await;
+    let owner = app.sign_up().await;
+    let todo_id = seed_todo(&app, &owner).await;
+
+    let (status, _) = app.request(&owner, Method::DELETE, &format!("/api/todo/remove/{todo_id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app.request(&owner, Method::GET, &format!("/api/todos/{todo_id}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn tags_and_categories_of_other_users_are_not_found() {
+    let app = TestApp::spawn().await;
+    let owner = app.sign_up().await;
+    let other = app.sign_up().await;
+    seed_todo(&app, &owner).await;
+
+    let (status, _) = app.request(&other, Method::DELETE, "/api/tag/rust", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app.request(&other, Method::DELETE, "/api/category/learning", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // another user's slugs can't be attached to your own todo either
+    let (status, _) = app
+        .request(
+            &other,
+            Method::POST,
+            "/api/todos",
+            Some(json!({ "todo": "Borrow a category", "description": "Not mine to use", "category_slug": "learning" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, body) = app.request(&owner, Method::GET, "/api/tag/all", None).await;
+    assert_eq!(body["data"], json!([{ "name": "rust", "slug": "rust" }]));
+}
+
+#[tokio::test]
+async fn daily_progress_of_other_users_is_not_found() {
+    let app = TestApp::spawn().await;
+    let owner = app.sign_up().await;
+    let other = app.sign_up().await;
+    let (progress_id, progress_todo_id) = seed_progress(&app, &owner).await;
+
+    let (status, _) = app
+        .request(&other, Method::GET, &format!("/api/progress/todo/{progress_todo_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app
+        .request(&other, Method::PUT, &format!("/api/progress/todo/{progress_todo_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app
+        .request(&other, Method::DELETE, &format!("/api/progress/todo/{progress_todo_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = app
+        .request(&other, Method::GET, &format!("/api/progress/todos/{progress_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    seed_category(&app, &other, "daily").await;
+
+    let (status, _) = app
+        .request(
+            &other,
+            Method::POST,
+            &format!("/api/progress/todo/create/{progress_id}"),
+            Some(json!({ "todo": "Sneak a todo in", "description": "Into someone else's day", "category_slug": "daily" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // the owner's progress todo is still there and untoggled
+    let (status, body) = app
+        .request(&owner, Method::GET, &format!("/api/progress/todo/{progress_todo_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["is_done"], false);
+
+    let (status, body) = app
+        .request(&owner, Method::GET, &format!("/api/progress/todos/{progress_id}"), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().map(Vec::len), Some(1));
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use protfolio_backend::utils::token::hash_token;
+use serde_json::json;
+use uuid::Uuid;
+
+use common::TestApp;
+
+#[tokio::test]
+async fn forgot_password_is_limited_per_email() {
+    let app = TestApp::spawn().await;
+    let email = format!("{}@example.com", Uuid::new_v4().simple());
+
+    for _ in 0..5 {
+        let (status, _) = app
+            .request_anonymous(Method::POST, "/api/user/password/forgot", Some(json!({ "email": email })))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    // casing doesn't open a new bucket
+    let (status, _) = app
+        .request_anonymous(Method::POST, "/api/user/password/forgot", Some(json!({ "email": email.to_uppercase() })))
+        .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    // requests without a known address don't share one bucket
+    let (status, _) = app
+        .request_anonymous(Method::POST, "/api/user/password/forgot", Some(json!({ "email": format!("other-{email}") })))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn a_reset_revokes_personal_access_tokens() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+
+    let (status, body) = app
+        .request(&user, Method::POST, "/api/user/tokens", Some(json!({ "name": "ci", "scopes": ["todos:read"] })))
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    // stands in for the emailed link
+    let token = Uuid::new_v4().simple().to_string();
+    sqlx::query("INSERT INTO email_tokens (user_id, purpose, token_hash, expires_at) VALUES ($1, 'password_reset', $2, now() + interval '1 hour')")
+        .bind(user.id)
+        .bind(hash_token(&token))
+        .execute(&app.state.pool)
+        .await
+        .unwrap();
+
+    let (status, body) = app
+        .request_anonymous(
+            Method::POST,
+            "/api/user/password/reset",
+            Some(json!({ "token": token, "new_password": "N3wPassw0rd!45" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let active: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM personal_access_tokens WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user.id)
+    .fetch_one(&app.state.pool)
+    .await
+    .unwrap();
+    assert_eq!(active, 0);
+}
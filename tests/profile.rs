@@ -0,0 +1,80 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+
+use common::TestApp;
+
+const PASSWORD: &str = "Passw0rd!23";
+
+#[tokio::test]
+async fn an_email_held_by_another_account_is_rejected() {
+    let app = TestApp::spawn().await;
+    let owner = app.sign_up().await;
+    let other = app.sign_up().await;
+
+    // casing doesn't make it a different address
+    let (status, body) = app
+        .request(
+            &other,
+            Method::PATCH,
+            "/api/user/me",
+            Some(json!({ "email": owner.email.to_uppercase(), "current_password": PASSWORD })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["message"], "User already exits");
+
+    let (status, body) = app
+        .request_anonymous(
+            Method::POST,
+            "/api/user/create",
+            Some(json!({ "name": "Late comer", "username": "late_comer_x", "email": owner.email.to_uppercase(), "password": PASSWORD })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["message"], "User already exits");
+
+    // changing the case of your own address is fine
+    let (status, body) = app
+        .request(
+            &owner,
+            Method::PATCH,
+            "/api/user/me",
+            Some(json!({ "email": owner.email.to_uppercase(), "current_password": PASSWORD })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+#[tokio::test]
+async fn a_password_change_revokes_personal_access_tokens() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+
+    let (status, body) = app
+        .request(&user, Method::POST, "/api/user/tokens", Some(json!({ "name": "ci", "scopes": ["todos:read"] })))
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+
+    let (status, _) = app.request_with_token(&token, Method::GET, "/api/todos", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app
+        .request(
+            &user,
+            Method::PUT,
+            "/api/user/password",
+            Some(json!({ "current_password": PASSWORD, "new_password": "N3wPassw0rd!45" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (status, _) = app.request_with_token(&token, Method::GET, "/api/todos", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // the session that changed it stays signed in
+    let (status, _) = app.request(&user, Method::GET, "/api/user/me", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
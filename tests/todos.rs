@@ -0,0 +1,214 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::{Value, json};
+
+use common::{TestApp, TestUser, create_todo, seed_category, seed_tag, titles};
+
+// two categories and three tags, plus a todo in `learning` tagged rust and async
+async fn seed(app: &TestApp, user: &TestUser) -> String {
+    for slug in ["learning", "reading"] {
+        seed_category(app, user, slug).await;
+    }
+    for slug in ["rust", "async", "books"] {
+        seed_tag(app, user, slug).await;
+    }
+
+    create_todo(app, user, "Read the async book", "learning", json!({ "tags_slug": ["rust", "async"] })).await
+}
+
+fn tag_slugs(body: &Value) -> Vec<&str> {
+    body["data"]["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| tag["slug"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn patch_changes_only_the_given_fields() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    let todo_id = seed(&app, &user).await;
+
+    let (_, before) = app.request(&user, Method::GET, &format!("/api/todos/{todo_id}"), None).await;
+
+    let (status, body) = app
+        .request(
+            &user,
+            Method::PATCH,
+            &format!("/api/todos/{todo_id}"),
+            Some(json!({
+                "todo": "Read the whole async book",
+                "category_slug": "reading",
+                "add_tags": ["books"],
+                "remove_tags": ["async"],
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["title"], "Read the whole async book");
+    assert_eq!(body["data"]["description"], "Chapters one to three");
+    assert_eq!(body["data"]["category"]["slug"], "reading");
+    assert_eq!(tag_slugs(&body), ["books", "rust"]);
+    assert_ne!(body["data"]["updated_at"], before["data"]["updated_at"]);
+}
+
+#[tokio::test]
+async fn legacy_put_updates_the_todo() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    let todo_id = seed(&app, &user).await;
+
+    let (status, body) = app
+        .request(
+            &user,
+            Method::PUT,
+            &format!("/api/todo/update/{todo_id}"),
+            Some(json!({ "description": "Only chapter one" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["title"], "Read the async book");
+    assert_eq!(body["data"]["description"], "Only chapter one");
+    assert_eq!(tag_slugs(&body), ["async", "rust"]);
+}
+
+#[tokio::test]
+async fn invalid_patches_leave_the_todo_untouched() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    let todo_id = seed(&app, &user).await;
+    let uri = format!("/api/todos/{todo_id}");
+
+    for patch in [
+        json!({}),
+        json!({ "tags_slug": ["rust"], "add_tags": ["books"] }),
+        json!({ "add_tags": ["books"], "remove_tags": ["books"] }),
+        json!({ "todo": "tiny" }),
+    ] {
+        let (status, _) = app.request(&user, Method::PATCH, &uri, Some(patch)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    // an unknown tag fails the whole patch, not just the tag part
+    let (status, _) = app
+        .request(
+            &user,
+            Method::PATCH,
+            &uri,
+            Some(json!({ "todo": "Renamed but rolled back", "add_tags": ["missing"] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, body) = app.request(&user, Method::GET, &uri, None).await;
+    assert_eq!(body["data"]["title"], "Read the async book");
+    assert_eq!(tag_slugs(&body), ["async", "rust"]);
+}
+
+#[tokio::test]
+async fn due_dates_drive_overdue_and_due_today() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    seed(&app, &user).await;
+
+    create_todo(&app, &user, "Late and minor", "learning", json!({ "priority": "P3", "due_on": "2026-03-09" })).await;
+    create_todo(&app, &user, "Late and urgent", "learning", json!({ "priority": "P1", "due_on": "2026-03-08" })).await;
+    create_todo(&app, &user, "Evening review", "learning", json!({ "due_on": "2026-03-10", "due_time": "18:30" })).await;
+    let morning = create_todo(
+        &app,
+        &user,
+        "Morning session",
+        "learning",
+        json!({ "priority": "P2", "due_on": "2026-03-10", "due_time": "08:00", "estimated_minutes": 45 }),
+    )
+    .await;
+
+    let (status, body) = app.request(&user, Method::GET, "/api/todos/overdue?day=2026-03-10", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(titles(&body), ["Late and urgent", "Late and minor"]);
+
+    let (status, body) = app.request(&user, Method::GET, "/api/todos/due-today?day=2026-03-10", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(titles(&body), ["Morning session", "Evening review"]);
+    assert_eq!(body["data"][0]["due_on"], "2026-03-10");
+    assert_eq!(body["data"][0]["due_time"], "08:00");
+    assert_eq!(body["data"][0]["estimated_minutes"], 45);
+    assert_eq!(body["data"][1]["priority"], "P4");
+
+    // clearing the date takes the time with it
+    let (status, body) = app
+        .request(&user, Method::PATCH, &format!("/api/todos/{morning}"), Some(json!({ "due_on": null })))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["due_on"], Value::Null);
+    assert_eq!(body["data"]["due_time"], Value::Null);
+    assert_eq!(body["data"]["estimated_minutes"], 45);
+
+    let (status, _) = app.request(&user, Method::GET, "/api/todos/overdue?day=10-03-2026", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn planning_fields_are_validated() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    let todo_id = seed(&app, &user).await;
+
+    for plan in [
+        json!({ "due_time": "09:00" }),
+        json!({ "estimated_minutes": 0 }),
+        json!({ "priority": "P5" }),
+        json!({ "due_on": "tomorrow" }),
+    ] {
+        let mut body = json!({ "todo": "Badly planned", "description": "Never saved", "category_slug": "learning" });
+        body.as_object_mut().unwrap().extend(plan.as_object().unwrap().clone());
+
+        let (status, _) = app.request(&user, Method::POST, "/api/todos", Some(body)).await;
+        assert!(status.is_client_error(), "{plan} was accepted");
+    }
+
+    // the todo has no due date to hang the time on
+    let (status, body) = app
+        .request(&user, Method::PATCH, &format!("/api/todos/{todo_id}"), Some(json!({ "due_time": "09:00" })))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["message"], "A due time needs a due date");
+}
+
+#[tokio::test]
+async fn daily_progress_todos_are_sorted_by_priority() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+    seed(&app, &user).await;
+
+    let (_, body) = app
+        .request(&user, Method::POST, "/api/progress", Some(json!({ "day": "2026-03-10" })))
+        .await;
+    let progress_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    for (todo, priority) in [("Low priority first", "P4"), ("Urgent second", "P1"), ("Normal third", "P3")] {
+        let (status, _) = app
+            .request(
+                &user,
+                Method::POST,
+                &format!("/api/progress/todo/create/{progress_id}"),
+                Some(json!({ "todo": todo, "description": "Part of the day", "category_slug": "learning", "priority": priority })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (_, body) = app
+        .request(&user, Method::GET, &format!("/api/progress/todos/{progress_id}"), None)
+        .await;
+    let order: Vec<&str> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|todo| todo["todo_title"].as_str().unwrap())
+        .collect();
+    assert_eq!(order, ["Urgent second", "Normal third", "Low priority first"]);
+}
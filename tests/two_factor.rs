@@ -0,0 +1,92 @@
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+
+use common::{TestApp, TestUser};
+
+const PASSWORD: &str = "Passw0rd!23";
+
+async fn start_login(app: &TestApp, user: &TestUser) -> (StatusCode, String) {
+    let (status, body) = app
+        .request_anonymous(Method::POST, "/api/user/login", Some(json!({ "email": user.email, "password": PASSWORD })))
+        .await;
+
+    (status, body["data"]["challenge_token"].as_str().unwrap_or_default().to_string())
+}
+
+#[tokio::test]
+async fn wrong_codes_lock_the_account() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+
+    // recovery codes are enough to get a challenge, the secret itself isn't used
+    sqlx::query("UPDATE users SET totp_enabled_at = now() WHERE id = $1")
+        .bind(user.id)
+        .execute(&app.state.pool)
+        .await
+        .unwrap();
+
+    // a fresh challenge for every guess doesn't reset the count
+    for _ in 0..5 {
+        let (status, challenge_token) = start_login(&app, &user).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = app
+            .request_anonymous(
+                Method::POST,
+                "/api/user/login/2fa",
+                Some(json!({ "challenge_token": challenge_token, "code": "0000000000" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    let (status, _) = start_login(&app, &user).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    let failures: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM audit_log WHERE user_id = $1 AND event = 'login_failed'")
+        .bind(user.id)
+        .fetch_one(&app.state.pool)
+        .await
+        .unwrap();
+    assert_eq!(failures, 5);
+}
+
+#[tokio::test]
+async fn a_password_alone_does_not_restore_a_deleted_account() {
+    let app = TestApp::spawn().await;
+    let user = app.sign_up().await;
+
+    sqlx::query("UPDATE users SET totp_enabled_at = now(), deleted_at = now() WHERE id = $1")
+        .bind(user.id)
+        .execute(&app.state.pool)
+        .await
+        .unwrap();
+
+    let (status, challenge_token) = start_login(&app, &user).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = app
+        .request_anonymous(
+            Method::POST,
+            "/api/user/login/2fa",
+            Some(json!({ "challenge_token": challenge_token, "code": "0000000000" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let still_deleted: bool = sqlx::query_scalar("SELECT deleted_at IS NOT NULL FROM users WHERE id = $1")
+        .bind(user.id)
+        .fetch_one(&app.state.pool)
+        .await
+        .unwrap();
+    assert!(still_deleted);
+
+    let restores: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM audit_log WHERE user_id = $1 AND event = 'account_restored'")
+        .bind(user.id)
+        .fetch_one(&app.state.pool)
+        .await
+        .unwrap();
+    assert_eq!(restores, 0);
+}